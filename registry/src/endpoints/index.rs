@@ -0,0 +1,62 @@
+use actix_web::{
+    http::header::{ETAG, IF_NONE_MATCH},
+    web, HttpRequest, HttpResponse, Responder,
+};
+use sha2::{Digest, Sha256};
+
+use pesde::{names::PackageName, source::git_index::GitBasedSource};
+
+use crate::{error::Error, AppState};
+
+fn raw_file_response(request: &HttpRequest, contents: String) -> HttpResponse {
+    let etag = format!("\"{:x}\"", Sha256::digest(contents.as_bytes()));
+
+    if request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag))
+    {
+        return HttpResponse::NotModified()
+            .insert_header((ETAG, etag))
+            .finish();
+    }
+
+    HttpResponse::Ok()
+        .insert_header((ETAG, etag))
+        .content_type("application/toml")
+        .body(contents)
+}
+
+pub async fn get_index_config(
+    request: HttpRequest,
+    app_state: web::Data<AppState>,
+) -> Result<impl Responder, Error> {
+    let source = app_state.source.lock().unwrap();
+
+    match source.read_file(["config.toml"], &app_state.project, None)? {
+        Some(config) => Ok(raw_file_response(&request, config)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
+
+pub async fn get_index_file(
+    request: HttpRequest,
+    app_state: web::Data<AppState>,
+    path: web::Path<(String, String)>,
+) -> Result<impl Responder, Error> {
+    let (scope, name) = path.into_inner();
+
+    // only package files are served, not other files in the index such as scope information
+    let Ok(name) = format!("{scope}/{name}").parse::<PackageName>() else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+    let (scope, name_part) = name.as_str();
+
+    let source = app_state.source.lock().unwrap();
+
+    match source.read_file([scope, name_part], &app_state.project, None)? {
+        Some(file) => Ok(raw_file_response(&request, file)),
+        None => Ok(HttpResponse::NotFound().finish()),
+    }
+}
@@ -1,3 +1,4 @@
+pub mod index;
 pub mod package_version;
 pub mod package_versions;
 pub mod publish_version;
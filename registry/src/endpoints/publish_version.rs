@@ -320,6 +320,18 @@ pub async fn publish_package(
                     // workspace specifiers are to be transformed into Pesde specifiers by the sender
                     return Err(Error::InvalidArchive);
                 }
+                DependencySpecifiers::Path(_) => {
+                    // path specifiers only make sense on the machine they were written on
+                    return Err(Error::InvalidArchive);
+                }
+                DependencySpecifiers::Url(_) => {
+                    // archives behind arbitrary urls may disappear or change
+                    return Err(Error::InvalidArchive);
+                }
+                DependencySpecifiers::Custom(_) => {
+                    // custom sources are only registered in the projects of the tools using them
+                    return Err(Error::InvalidArchive);
+                }
             }
         }
 
@@ -362,6 +374,8 @@ pub async fn publish_package(
             docs,
 
             dependencies,
+
+            archive_hash: Some(format!("{:x}", Sha256::digest(&bytes))),
         };
 
         let this_version = entries
@@ -9,7 +9,7 @@ use log::info;
 use std::{env::current_dir, fs::create_dir_all, path::PathBuf, sync::Mutex};
 
 use pesde::{
-    source::{pesde::PesdePackageSource, traits::PackageSource},
+    source::{git_index::GitBasedSource, pesde::PesdePackageSource},
     AuthConfig, Project,
 };
 
@@ -153,6 +153,18 @@ async fn run(with_sentry: bool) -> std::io::Result<()> {
                             .to(endpoints::search::search_packages)
                             .wrap(from_fn(auth::read_mw)),
                     )
+                    .route(
+                        "/index/config.toml",
+                        web::get()
+                            .to(endpoints::index::get_index_config)
+                            .wrap(from_fn(auth::read_mw)),
+                    )
+                    .route(
+                        "/index/{scope}/{name}",
+                        web::get()
+                            .to(endpoints::index::get_index_file)
+                            .wrap(from_fn(auth::read_mw)),
+                    )
                     .route(
                         "/packages/{name}",
                         web::get()
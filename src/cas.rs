@@ -0,0 +1,707 @@
+use crate::{
+    lockfile::{DownloadedGraph, Lockfile},
+    source::{
+        fs::{FSEntry, PackageFS},
+        refs::PackageRefs,
+    },
+    Project, LOCKFILE_FILE_NAME,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeSet, HashSet},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+/// The file in the data directory recording the projects using it
+const PROJECTS_FILE_NAME: &str = "projects.toml";
+
+/// The directories of the CAS holding the cached file systems of packages
+const INDEX_DIRS: &[&str] = &["index", "git_index", "wally_index", "url"];
+
+/// The directory of the CAS corrupted files are moved to
+const QUARANTINE_DIR: &str = ".quarantine";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegisteredProjects {
+    #[serde(default)]
+    projects: BTreeSet<PathBuf>,
+}
+
+/// Options for collecting the garbage of the CAS
+#[derive(Debug, Clone)]
+pub struct GcOptions {
+    /// Unreferenced files younger than this are kept, as they may belong to an install in progress
+    pub grace_period: Duration,
+    /// Only report what would be removed, without removing anything
+    pub dry_run: bool,
+}
+
+/// The result of verifying the CAS
+#[derive(Debug, Default, Clone)]
+pub struct VerifyReport {
+    /// The amount of files which were checked
+    pub checked: usize,
+    /// The hashes of the files whose contents didn't match them, and the paths they were quarantined at
+    pub corrupted: Vec<(String, PathBuf)>,
+    /// The cached file systems which referenced corrupted or missing files, or couldn't be read, and were removed
+    pub invalidated: Vec<PathBuf>,
+}
+
+/// The result of collecting the garbage of the CAS
+#[derive(Debug, Default, Clone)]
+pub struct GcReport {
+    /// The files which were removed (or would have been, in a dry run), along with their sizes
+    pub removed: Vec<(PathBuf, u64)>,
+    /// The amount of files which were kept
+    pub kept: usize,
+    /// The size of the files which were kept
+    pub kept_size: u64,
+}
+
+impl GcReport {
+    /// The size of the files which were removed
+    pub fn removed_size(&self) -> u64 {
+        self.removed.iter().map(|(_, size)| size).sum()
+    }
+}
+
+/// The path of the cached file system of a package, if its source caches one
+fn index_file(project: &Project, pkg_ref: &PackageRefs) -> Option<PathBuf> {
+    match pkg_ref {
+        PackageRefs::Pesde(pkg_ref) => Some(pkg_ref.index_file(project)),
+        #[cfg(feature = "wally-compat")]
+        PackageRefs::Wally(pkg_ref) => Some(pkg_ref.index_file(project)),
+        PackageRefs::Git(pkg_ref) => Some(pkg_ref.index_file(project)),
+        PackageRefs::Url(pkg_ref) => Some(pkg_ref.index_file(project)),
+        PackageRefs::Workspace(_) | PackageRefs::Path(_) | PackageRefs::Custom(_) => None,
+    }
+}
+
+fn age(metadata: &std::fs::Metadata) -> Duration {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default()
+}
+
+/// Whether the file is hard linked from elsewhere, such as a project's packages folder
+fn is_linked(metadata: &std::fs::Metadata) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.nlink() > 1
+    }
+    // the link count isn't exposed on other platforms, and removing a linked file only undoes the deduplication
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        false
+    }
+}
+
+fn remove_file(path: &Path) -> std::io::Result<()> {
+    // files in the CAS are read-only, which prevents removing them on Windows
+    #[cfg(windows)]
+    {
+        let mut permissions = std::fs::metadata(path)?.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(path, permissions)?;
+    }
+
+    std::fs::remove_file(path)
+}
+
+/// Lists the files in a directory, recursively
+fn files_in(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    let mut dirs = vec![dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+
+        for entry in entries {
+            let entry = entry?;
+
+            if entry.file_type()?.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Lists the files of the CAS itself, which are stored as `ab/cdef...` for the hash `abcdef...`
+fn cas_files(cas_dir: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut files = vec![];
+
+    for entry in std::fs::read_dir(cas_dir)? {
+        let entry = entry?;
+        let prefix = entry.file_name().to_string_lossy().to_string();
+
+        if prefix.len() != 2 || !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        for file in std::fs::read_dir(entry.path())? {
+            let file = file?;
+            files.push((
+                format!("{prefix}{}", file.file_name().to_string_lossy()),
+                file.path(),
+            ));
+        }
+    }
+
+    Ok(files)
+}
+
+/// The hashes of the files of a package's file system
+pub(crate) fn file_hashes(fs: &PackageFS) -> impl Iterator<Item = &str> {
+    let entries = match fs {
+        PackageFS::CAS(entries) => Some(entries.values()),
+        PackageFS::Copy(..) => None,
+    };
+
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            FSEntry::File(hash, _) => Some(hash.as_str()),
+            FSEntry::Directory => None,
+        })
+}
+
+/// Whether the contents of the file stored in the CAS under a hash still have that hash
+pub(crate) fn verify_cas_file(cas_dir: &Path, hash: &str) -> std::io::Result<bool> {
+    let (prefix, rest) = hash.split_at(2);
+    let mut file = std::fs::File::open(cas_dir.join(prefix).join(rest))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+
+    Ok(format!("{:x}", hasher.finalize()) == hash)
+}
+
+/// Moves a corrupted file out of the CAS, so it is fetched again instead of being linked
+pub(crate) fn quarantine_cas_file(cas_dir: &Path, hash: &str) -> std::io::Result<PathBuf> {
+    let (prefix, rest) = hash.split_at(2);
+    let quarantine_dir = cas_dir.join(QUARANTINE_DIR);
+    std::fs::create_dir_all(&quarantine_dir)?;
+
+    let quarantined = quarantine_dir.join(hash);
+    std::fs::rename(cas_dir.join(prefix).join(rest), &quarantined)?;
+
+    Ok(quarantined)
+}
+
+/// Removes the cached file system of a package referencing a corrupted file, so the package is downloaded again
+pub(crate) fn invalidate_index_file(
+    project: &Project,
+    pkg_ref: &PackageRefs,
+) -> std::io::Result<()> {
+    let Some(path) = index_file(project, pkg_ref) else {
+        return Ok(());
+    };
+
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+impl Project {
+    /// Records this project as using the data directory, so the CAS keeps its dependencies when collecting garbage
+    pub fn register(&self) -> Result<(), errors::RegisterError> {
+        let package_dir = self
+            .package_dir
+            .canonicalize()
+            .unwrap_or_else(|_| self.package_dir.clone());
+
+        self.update_registered_projects(|projects| projects.insert(package_dir))
+    }
+
+    /// Updates the registered projects while holding their lock, writing them back if `update` returns true
+    fn update_registered_projects(
+        &self,
+        update: impl FnOnce(&mut BTreeSet<PathBuf>) -> bool,
+    ) -> Result<(), errors::RegisterError> {
+        let path = self.data_dir.join(PROJECTS_FILE_NAME);
+        // every invocation of the cli registers its project, so concurrent ones would otherwise drop each other's
+        let _lock = self.lock_path(&path)?;
+
+        let mut registered = match std::fs::read_to_string(&path) {
+            Ok(s) => toml::from_str(&s)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => RegisteredProjects::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        if !update(&mut registered.projects) {
+            return Ok(());
+        }
+
+        // written to a temporary file first, so the projects aren't lost if writing them is interrupted
+        let mut file = tempfile::NamedTempFile::new_in(&self.data_dir)?;
+        file.write_all(toml::to_string(&registered)?.as_bytes())?;
+        file.persist(&path).map_err(|e| e.error)?;
+
+        Ok(())
+    }
+
+    /// The projects recorded as using the data directory
+    pub fn registered_projects(&self) -> Result<BTreeSet<PathBuf>, errors::RegisterError> {
+        match std::fs::read_to_string(self.data_dir.join(PROJECTS_FILE_NAME)) {
+            Ok(s) => Ok(toml::from_str::<RegisteredProjects>(&s)?.projects),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Removes the cached file systems of packages no registered project's lockfile uses, then the files in the CAS
+    /// no remaining cached file system references and which aren't linked into any project.
+    ///
+    /// Only projects recorded with [`Project::register`] are considered, so the dependencies of a project which
+    /// hasn't been registered since the data directory was last used by it are collected like any other garbage
+    pub fn collect_cas_garbage(&self, options: &GcOptions) -> Result<GcReport, errors::GcError> {
+        let mut report = GcReport::default();
+
+        // mark the cached file systems of the packages in the lockfiles of registered projects
+        let mut live_index_files = HashSet::new();
+        let registered = self.registered_projects()?;
+        // projects which were deleted, or which no longer have a lockfile, are forgotten
+        let projects = registered
+            .iter()
+            .filter(|project_dir| project_dir.join(LOCKFILE_FILE_NAME).is_file())
+            .cloned()
+            .collect::<BTreeSet<_>>();
+
+        for project_dir in &projects {
+            let lockfile_path = project_dir.join(LOCKFILE_FILE_NAME);
+            let lockfile = std::fs::read_to_string(&lockfile_path)
+                .map_err(|e| errors::GcError::ReadLockfile(lockfile_path.clone(), e.into()))?
+                .parse::<Lockfile>()
+                .map_err(|e| errors::GcError::ReadLockfile(lockfile_path, e))?;
+
+            live_index_files.extend(index_files(self, &lockfile.graph));
+        }
+
+        let mut live_hashes = HashSet::new();
+
+        for index_dir in INDEX_DIRS {
+            for path in files_in(&self.cas_dir.join(index_dir))? {
+                let metadata = std::fs::metadata(&path)?;
+
+                // the hashes of wally archives are kept beside their file systems
+                let owner = match path.extension() {
+                    Some(ext) if *index_dir == "wally_index" && ext == "sha256" => {
+                        path.with_extension("")
+                    }
+                    _ => path.clone(),
+                };
+
+                if !live_index_files.contains(&owner) && age(&metadata) >= options.grace_period {
+                    if options.dry_run {
+                        report.removed.push((path, metadata.len()));
+                        continue;
+                    }
+
+                    // downloads write the cached file system while holding its lock, and may have
+                    // rewritten it while the lock was being waited for
+                    let _lock = self.lock_path(&owner)?;
+                    match std::fs::metadata(&path) {
+                        Ok(current) if age(&current) < options.grace_period => {}
+                        Ok(_) => {
+                            remove_file(&path)?;
+                            report.removed.push((path, metadata.len()));
+                            continue;
+                        }
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+
+                report.kept += 1;
+                report.kept_size += metadata.len();
+
+                if owner != path {
+                    continue;
+                }
+
+                let contents = std::fs::read_to_string(&path)?;
+                let fs = toml::from_str::<PackageFS>(&contents)
+                    .map_err(|e| errors::GcError::ReadIndexFile(path.clone(), e))?;
+
+                live_hashes.extend(file_hashes(&fs).map(ToString::to_string));
+            }
+        }
+
+        for (hash, path) in cas_files(&self.cas_dir)? {
+            let metadata = std::fs::metadata(&path)?;
+
+            if live_hashes.contains(&hash)
+                || is_linked(&metadata)
+                || age(&metadata) < options.grace_period
+            {
+                report.kept += 1;
+                report.kept_size += metadata.len();
+                continue;
+            }
+
+            if !options.dry_run {
+                remove_file(&path)?;
+            }
+
+            report.removed.push((path, metadata.len()));
+        }
+
+        // temporary files left behind by interrupted downloads
+        for path in files_in(&self.cas_dir.join(".tmp"))? {
+            let metadata = std::fs::metadata(&path)?;
+
+            if age(&metadata) < options.grace_period {
+                continue;
+            }
+
+            if !options.dry_run {
+                remove_file(&path)?;
+            }
+
+            report.removed.push((path, metadata.len()));
+        }
+
+        // every locked path leaves a lock file behind, so those which no one holds are removed as well. they aren't
+        // reported, as they aren't part of the CAS
+        if !options.dry_run {
+            for path in files_in(&self.data_dir.join(crate::lock::LOCKS_DIR))? {
+                let metadata = std::fs::metadata(&path)?;
+
+                if age(&metadata) >= options.grace_period {
+                    crate::lock::remove_unlocked(&path)?;
+                }
+            }
+        }
+
+        if projects.len() != registered.len() && !options.dry_run {
+            // only the forgotten projects are removed, keeping those registered while collecting
+            self.update_registered_projects(|current| {
+                let len = current.len();
+                current.retain(|project_dir| {
+                    projects.contains(project_dir) || !registered.contains(project_dir)
+                });
+                current.len() != len
+            })?;
+        }
+
+        Ok(report)
+    }
+
+    /// The path of the cached file system of a package, if its source caches one. It is removed when it references
+    /// corrupted files, and written again once the package is downloaded again
+    pub fn cached_fs_path(&self, pkg_ref: &PackageRefs) -> Option<PathBuf> {
+        index_file(self, pkg_ref)
+    }
+
+    /// Rehashes every file in the CAS, quarantining those whose contents don't match their hash, and removes the
+    /// cached file systems referencing them so their packages are downloaded again
+    pub fn verify_cas(&self) -> Result<VerifyReport, errors::VerifyError> {
+        let mut report = VerifyReport::default();
+        let mut corrupted = HashSet::new();
+
+        for (hash, _) in cas_files(&self.cas_dir)? {
+            report.checked += 1;
+
+            if verify_cas_file(&self.cas_dir, &hash)? {
+                continue;
+            }
+
+            log::warn!("file {hash} in the cas is corrupted");
+
+            let quarantined = quarantine_cas_file(&self.cas_dir, &hash)?;
+            corrupted.insert(hash.clone());
+            report.corrupted.push((hash, quarantined));
+        }
+
+        for index_dir in INDEX_DIRS {
+            for path in files_in(&self.cas_dir.join(index_dir))? {
+                if *index_dir == "wally_index"
+                    && path.extension().is_some_and(|ext| ext == "sha256")
+                {
+                    continue;
+                }
+
+                let contents = std::fs::read_to_string(&path)?;
+                let valid = toml::from_str::<PackageFS>(&contents).is_ok_and(|fs| {
+                    file_hashes(&fs).all(|hash| {
+                        let (prefix, rest) = hash.split_at(2);
+                        !corrupted.contains(hash) && self.cas_dir.join(prefix).join(rest).is_file()
+                    })
+                });
+
+                if valid {
+                    continue;
+                }
+
+                let _lock = self.lock_path(&path)?;
+                remove_file(&path)?;
+                report.invalidated.push(path);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+fn index_files<'a>(
+    project: &'a Project,
+    graph: &'a DownloadedGraph,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    graph
+        .values()
+        .flat_map(|versions| versions.values())
+        .filter_map(|node| index_file(project, &node.node.pkg_ref))
+}
+
+/// Errors that can occur when maintaining the CAS
+pub mod errors {
+    use std::path::PathBuf;
+    use thiserror::Error;
+
+    /// Errors that can occur when recording a project as using the data directory
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RegisterError {
+        /// An error occurred interacting with the file system
+        #[error("error interacting with the file system")]
+        Io(#[from] std::io::Error),
+
+        /// An error occurred deserializing the registered projects
+        #[error("error deserializing registered projects")]
+        Deserialize(#[from] toml::de::Error),
+
+        /// An error occurred serializing the registered projects
+        #[error("error serializing registered projects")]
+        Serialize(#[from] toml::ser::Error),
+    }
+
+    /// Errors that can occur when verifying the CAS
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum VerifyError {
+        /// An error occurred interacting with the file system
+        #[error("error interacting with the file system")]
+        Io(#[from] std::io::Error),
+    }
+
+    /// Errors that can occur when collecting the garbage of the CAS
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum GcError {
+        /// An error occurred interacting with the file system
+        #[error("error interacting with the file system")]
+        Io(#[from] std::io::Error),
+
+        /// An error occurred reading the registered projects
+        #[error("error reading registered projects")]
+        Register(#[from] RegisterError),
+
+        /// An error occurred reading the lockfile of a registered project
+        #[error("error reading lockfile at {0}")]
+        ReadLockfile(PathBuf, #[source] crate::errors::LockfileReadError),
+
+        /// An error occurred deserializing a cached file system
+        #[error("error deserializing cached file system at {0}")]
+        ReadIndexFile(PathBuf, #[source] toml::de::Error),
+
+        /// An error occurred serializing the registered projects
+        #[error("error serializing registered projects")]
+        Serialize(#[from] toml::ser::Error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthConfig;
+
+    const LOCKFILE: &str = r#"name = "acme/app"
+version = "0.1.0"
+target = "luau"
+
+[graph."acme/foo"."0.1.0 luau"]
+ty = "standard"
+
+[graph."acme/foo"."0.1.0 luau".target]
+environment = "luau"
+
+[graph."acme/foo"."0.1.0 luau".pkg_ref]
+ref_ty = "pesde"
+name = "acme/foo"
+version = "0.1.0"
+index_url = "file:///index"
+
+[graph."acme/foo"."0.1.0 luau".pkg_ref.target]
+environment = "luau"
+"#;
+
+    fn project(root: &Path) -> Project {
+        let project = Project::new(
+            root.join("app"),
+            None::<PathBuf>,
+            root.join("data"),
+            root.join("cas"),
+            AuthConfig::default(),
+        );
+        std::fs::create_dir_all(project.package_dir()).unwrap();
+        std::fs::create_dir_all(project.data_dir()).unwrap();
+
+        project
+    }
+
+    /// Stores a file in the CAS, returning its hash
+    fn store(project: &Project, contents: &str) -> String {
+        let hash = crate::util::hash(contents);
+        let (prefix, rest) = hash.split_at(2);
+        let path = project.cas_dir().join(prefix).join(rest);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+
+        hash
+    }
+
+    /// Writes a cached file system holding a single file
+    fn write_index_file(path: &Path, hash: &str) {
+        let fs = PackageFS::CAS(
+            [("init.luau".into(), FSEntry::File(hash.to_string(), None))]
+                .into_iter()
+                .collect(),
+        );
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, toml::to_string(&fs).unwrap()).unwrap();
+    }
+
+    fn cas_path(project: &Project, hash: &str) -> PathBuf {
+        let (prefix, rest) = hash.split_at(2);
+        project.cas_dir().join(prefix).join(rest)
+    }
+
+    fn collect(project: &Project, dry_run: bool) -> GcReport {
+        project
+            .collect_cas_garbage(&GcOptions {
+                grace_period: Duration::ZERO,
+                dry_run,
+            })
+            .unwrap()
+    }
+
+    /// Sets up a registered project depending on `acme/foo`, and the cached file system of an unused package,
+    /// returning the paths of both cached file systems and the hashes of their files
+    fn setup(project: &Project) -> ((PathBuf, String), (PathBuf, String)) {
+        std::fs::write(project.package_dir().join(LOCKFILE_FILE_NAME), LOCKFILE).unwrap();
+        project.register().unwrap();
+
+        let lockfile = LOCKFILE.parse::<Lockfile>().unwrap();
+        let live = index_files(project, &lockfile.graph).next().unwrap();
+        let live_hash = store(project, "return 'live'");
+        write_index_file(&live, &live_hash);
+
+        let dead = project.cas_dir().join("index").join("acme+bar/0.1.0/luau");
+        let dead_hash = store(project, "return 'dead'");
+        write_index_file(&dead, &dead_hash);
+
+        ((live, live_hash), (dead, dead_hash))
+    }
+
+    #[test]
+    fn keeps_what_registered_projects_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path());
+        let ((live, live_hash), (dead, dead_hash)) = setup(&project);
+
+        let report = collect(&project, false);
+
+        assert!(live.is_file());
+        assert!(cas_path(&project, &live_hash).is_file());
+        assert!(!dead.exists());
+        assert!(!cas_path(&project, &dead_hash).exists());
+        assert_eq!(report.removed.len(), 2);
+        assert_eq!(report.kept, 2);
+    }
+
+    #[test]
+    fn dry_runs_remove_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path());
+        let ((live, _), (dead, dead_hash)) = setup(&project);
+
+        let report = collect(&project, true);
+
+        assert!(live.is_file());
+        assert!(dead.is_file());
+        assert!(cas_path(&project, &dead_hash).is_file());
+        assert!(report.removed.iter().any(|(path, _)| *path == dead));
+    }
+
+    #[test]
+    fn forgets_projects_without_lockfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path());
+        let ((live, live_hash), _) = setup(&project);
+
+        std::fs::remove_file(project.package_dir().join(LOCKFILE_FILE_NAME)).unwrap();
+        collect(&project, false);
+
+        assert!(project.registered_projects().unwrap().is_empty());
+        assert!(!live.exists());
+        assert!(!cas_path(&project, &live_hash).exists());
+    }
+
+    #[test]
+    fn removes_unlocked_lock_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path());
+        setup(&project);
+        let locks_dir = project.data_dir().join(crate::lock::LOCKS_DIR);
+
+        drop(project.lock_path(dir.path().join("released")).unwrap());
+        let _held = project.lock_path(dir.path().join("held")).unwrap();
+        let locks = files_in(&locks_dir).unwrap().len();
+        assert!(locks >= 2);
+
+        collect(&project, true);
+        assert_eq!(files_in(&locks_dir).unwrap().len(), locks);
+
+        // including the lock files created while collecting
+        collect(&project, false);
+        assert_eq!(files_in(&locks_dir).unwrap().len(), 1);
+        assert!(project.lock_path(dir.path().join("released")).is_ok());
+    }
+
+    #[test]
+    fn keeps_every_concurrent_registration() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = project(dir.path());
+
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let member = project.for_member(dir.path().join(format!("member-{i}")));
+                scope.spawn(move || member.register().unwrap());
+            }
+        });
+
+        assert_eq!(project.registered_projects().unwrap().len(), 8);
+        assert!(!project.data_dir().read_dir().unwrap().any(|entry| entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".tmp")));
+    }
+}
@@ -9,10 +9,16 @@ use pesde::{
     manifest::target::TargetKind,
     names::PackageNames,
     source::{
-        git::{specifier::GitDependencySpecifier, GitPackageSource},
+        git::{
+            specifier::{GitDependencySpecifier, GitVersion},
+            GitPackageSource,
+        },
+        path::{specifier::PathDependencySpecifier, PathPackageSource},
         pesde::{specifier::PesdeDependencySpecifier, PesdePackageSource},
+        refs::PackageRefs,
         specifiers::DependencySpecifiers,
         traits::PackageSource,
+        url::{specifier::UrlDependencySpecifier, UrlPackageSource},
         workspace::WorkspacePackageSource,
         PackageSources,
     },
@@ -47,7 +53,7 @@ pub struct AddCommand {
 }
 
 impl AddCommand {
-    pub fn run(self, project: Project) -> anyhow::Result<()> {
+    pub fn run(self, project: Project, reqwest: reqwest::blocking::Client) -> anyhow::Result<()> {
         let manifest = project
             .deser_manifest()
             .context("failed to read manifest")?;
@@ -108,7 +114,13 @@ impl AddCommand {
                 PackageSources::Git(GitPackageSource::new(url.clone())),
                 DependencySpecifiers::Git(GitDependencySpecifier {
                     repo: url.clone(),
-                    rev: rev.to_string(),
+                    version: if let Some(branch) = rev.strip_prefix("branch:") {
+                        GitVersion::Branch(branch.to_string())
+                    } else if let Some(tag) = rev.strip_prefix("tag:") {
+                        GitVersion::Tag(tag.to_string())
+                    } else {
+                        GitVersion::Rev(rev.to_string())
+                    },
                     path: None,
                 }),
             ),
@@ -122,17 +134,27 @@ impl AddCommand {
                     },
                 ),
             ),
+            AnyPackageIdentifier::Path(path) => (
+                PackageSources::Path(PathPackageSource),
+                DependencySpecifiers::Path(PathDependencySpecifier { path: path.clone() }),
+            ),
+            AnyPackageIdentifier::Archive(url) => (
+                PackageSources::Url(UrlPackageSource::new(url.clone())),
+                DependencySpecifiers::Url(UrlDependencySpecifier {
+                    url: url.clone(),
+                    sha256: None,
+                }),
+            ),
         };
         source
-            .refresh(&project)
+            .refresh(&project, &reqwest)
             .context("failed to refresh package source")?;
 
-        let Some(version_id) = source
-            .resolve(&specifier, &project, manifest.target.kind())
+        let Some((version_id, pkg_ref)) = source
+            .resolve(&specifier, &project, &reqwest, manifest.target.kind())
             .context("failed to resolve package")?
             .1
             .pop_last()
-            .map(|(v_id, _)| v_id)
         else {
             log::error!("no versions found for package {specifier}");
 
@@ -162,6 +184,21 @@ impl AddCommand {
                 .map(|s| s.to_string())
                 .unwrap_or(url.path.to_string()),
             AnyPackageIdentifier::Workspace(versioned) => versioned.0.as_str().1.to_string(),
+            AnyPackageIdentifier::Path(path) => path
+                .file_name()
+                .map(|s| s.to_string())
+                .unwrap_or(path.to_string()),
+            AnyPackageIdentifier::Archive(url) => url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .map(|s| {
+                    s.trim_end_matches(".gz")
+                        .trim_end_matches(".tar")
+                        .trim_end_matches(".tgz")
+                        .to_string()
+                })
+                .filter(|s| !s.is_empty())
+                .unwrap_or(url.to_string()),
         });
 
         let field = &mut manifest[dependency_key]
@@ -206,9 +243,16 @@ impl AddCommand {
             }
             DependencySpecifiers::Git(spec) => {
                 field["repo"] = toml_edit::value(spec.repo.to_bstring().to_string());
-                field["rev"] = toml_edit::value(spec.rev.clone());
+                match &spec.version {
+                    GitVersion::Branch(branch) => field["branch"] = toml_edit::value(branch),
+                    GitVersion::Tag(tag) => field["tag"] = toml_edit::value(tag),
+                    GitVersion::Rev(rev) => field["rev"] = toml_edit::value(rev),
+                }
 
-                println!("added git {}#{} to {}", spec.repo, spec.rev, dependency_key);
+                println!(
+                    "added git {}#{} to {}",
+                    spec.repo, spec.version, dependency_key
+                );
             }
             DependencySpecifiers::Workspace(spec) => {
                 field["workspace"] = toml_edit::value(spec.name.clone().to_string());
@@ -223,6 +267,36 @@ impl AddCommand {
                     spec.name, spec.version, dependency_key
                 );
             }
+            DependencySpecifiers::Path(spec) => {
+                field["path"] = toml_edit::value(spec.path.to_string());
+
+                println!(
+                    "added path {}@{} {} to {}",
+                    spec.path,
+                    version_id.version(),
+                    version_id.target(),
+                    dependency_key
+                );
+            }
+            DependencySpecifiers::Url(spec) => {
+                field["url"] = toml_edit::value(spec.url.to_string());
+
+                // pin the archive so the dependency can't change without the manifest changing
+                if let PackageRefs::Url(pkg_ref) = pkg_ref {
+                    field["sha256"] = toml_edit::value(pkg_ref.archive_hash);
+                }
+
+                println!(
+                    "added url {}@{} {} to {}",
+                    spec.url,
+                    version_id.version(),
+                    version_id.target(),
+                    dependency_key
+                );
+            }
+            DependencySpecifiers::Custom(_) => {
+                anyhow::bail!("custom sources can't be added from the cli")
+            }
         }
 
         project
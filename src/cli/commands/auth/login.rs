@@ -55,7 +55,7 @@ impl LoginCommand {
         println!("logging in into {index_url}");
 
         let source = PesdePackageSource::new(index_url.clone());
-        source.refresh(project).context("failed to refresh index")?;
+        source.refresh(project, reqwest).context("failed to refresh index")?;
 
         let config = source
             .config(project)
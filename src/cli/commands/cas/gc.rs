@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use clap::Args;
+use colored::Colorize;
+
+use crate::cli::commands::cas::format_size;
+use pesde::{cas::GcOptions, Project};
+
+#[derive(Debug, Args)]
+pub struct GcCommand {
+    /// Only report what would be removed
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Keep unused files younger than this amount of days
+    #[arg(long, default_value_t = 7)]
+    grace_period: u64,
+
+    /// List every removed file
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+impl GcCommand {
+    pub fn run(self, project: Project) -> anyhow::Result<()> {
+        let report = project
+            .collect_cas_garbage(&GcOptions {
+                grace_period: Duration::from_secs(self.grace_period * 24 * 60 * 60),
+                dry_run: self.dry_run,
+            })
+            .context("failed to collect garbage of the cas")?;
+
+        if self.verbose {
+            for (path, size) in &report.removed {
+                println!("{} ({})", path.display(), format_size(*size));
+            }
+        }
+
+        let removed = format!(
+            "{} {} files, freeing {}",
+            if self.dry_run {
+                "would remove"
+            } else {
+                "removed"
+            },
+            report.removed.len(),
+            format_size(report.removed_size())
+        );
+
+        println!("{}", removed.green());
+        println!(
+            "kept {} files, using {}",
+            report.kept,
+            format_size(report.kept_size)
+        );
+
+        Ok(())
+    }
+}
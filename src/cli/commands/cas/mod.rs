@@ -0,0 +1,48 @@
+use clap::Subcommand;
+use indicatif::MultiProgress;
+use pesde::Project;
+
+mod gc;
+mod verify;
+
+#[derive(Debug, Subcommand)]
+pub enum CasCommands {
+    /// Removes files from the CAS which no registered project uses
+    Gc(gc::GcCommand),
+
+    /// Checks the files in the CAS against their hashes
+    Verify(verify::VerifyCommand),
+}
+
+impl CasCommands {
+    pub fn run(
+        self,
+        project: Project,
+        multi: MultiProgress,
+        reqwest: reqwest::blocking::Client,
+    ) -> anyhow::Result<()> {
+        match self {
+            CasCommands::Gc(gc) => gc.run(project),
+            CasCommands::Verify(verify) => verify.run(project, multi, reqwest),
+        }
+    }
+}
+
+/// Formats a size in bytes for humans
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[unit])
+    } else {
+        format!("{size:.2} {}", UNITS[unit])
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+
+use anyhow::Context;
+use clap::Args;
+use colored::Colorize;
+use indicatif::MultiProgress;
+
+use crate::cli::download_graph;
+use pesde::{
+    errors::LockfileReadError,
+    lockfile::{DependencyGraph, DownloadedGraph},
+    Project,
+};
+
+#[derive(Debug, Args)]
+pub struct VerifyCommand {
+    /// Download the project's packages which are no longer cached, such as those which referenced corrupted files
+    #[arg(long)]
+    repair: bool,
+
+    /// The amount of threads to use for downloading
+    #[arg(short, long, default_value_t = 6, value_parser = clap::value_parser!(u64).range(1..=128))]
+    threads: u64,
+}
+
+fn undownloaded(graph: DownloadedGraph) -> DependencyGraph {
+    graph
+        .into_iter()
+        .map(|(name, versions)| {
+            (
+                name,
+                versions
+                    .into_iter()
+                    .map(|(version_id, node)| (version_id, node.node))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+impl VerifyCommand {
+    pub fn run(
+        self,
+        project: Project,
+        multi: MultiProgress,
+        reqwest: reqwest::blocking::Client,
+    ) -> anyhow::Result<()> {
+        let report = project.verify_cas().context("failed to verify cas")?;
+
+        for (hash, quarantined) in &report.corrupted {
+            println!(
+                "{} {hash}, quarantined at {}",
+                "corrupted".red(),
+                quarantined.display()
+            );
+        }
+
+        for path in &report.invalidated {
+            println!("{} {}", "invalidated".yellow(), path.display());
+        }
+
+        println!(
+            "checked {} files, {} corrupted, {} cached packages invalidated",
+            report.checked,
+            report.corrupted.len(),
+            report.invalidated.len()
+        );
+
+        if !self.repair {
+            if !report.invalidated.is_empty() {
+                println!(
+                    "the invalidated packages will be downloaded again by the next install, or now with `{} cas verify --repair`",
+                    env!("CARGO_BIN_NAME")
+                );
+            }
+
+            return Ok(());
+        }
+
+        let lockfile = match project.deser_lockfile() {
+            Ok(lockfile) => lockfile,
+            Err(LockfileReadError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!("{}", "no lockfile to repair the packages of".yellow());
+                return Ok(());
+            }
+            Err(e) => return Err(e).context("failed to read lockfile"),
+        };
+
+        let mut refreshed_sources = HashSet::new();
+
+        // packages which are still cached are skipped by their sources, so only the invalidated ones are fetched
+        let graphs = if lockfile.members.is_empty() {
+            vec![(project.clone(), lockfile.graph.clone())]
+        } else {
+            lockfile
+                .members
+                .keys()
+                .filter_map(|path| {
+                    let member = if path.as_str().is_empty() {
+                        project.clone()
+                    } else {
+                        project.for_member(path.to_path(project.package_dir()))
+                    };
+
+                    lockfile.member_graph(path).map(|graph| (member, graph))
+                })
+                .collect()
+        };
+
+        for (project, graph) in graphs {
+            download_graph(
+                &project,
+                &mut refreshed_sources,
+                &undownloaded(graph),
+                &multi,
+                &reqwest,
+                self.threads as usize,
+                false,
+                false,
+                false,
+                false,
+                "🩹 repairing packages".to_string(),
+                "🩹 repaired packages".to_string(),
+            )?;
+        }
+
+        println!(
+            "{}",
+            format!(
+                "repaired packages, run `{} install` to link them again",
+                env!("CARGO_BIN_NAME")
+            )
+            .green()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use crate::cli::config::{read_config, write_config};
+use clap::Args;
+use pesde::source::fs::LinkStrategy;
+
+#[derive(Debug, Args)]
+pub struct LinkStrategyCommand {
+    /// The new link strategy (auto, reflink, hardlink, symlink or copy), don't pass any value to check the current link strategy
+    #[arg(index = 1)]
+    strategy: Option<LinkStrategy>,
+
+    /// Resets the link strategy to the default value
+    #[arg(short, long, conflicts_with = "strategy")]
+    reset: bool,
+}
+
+impl LinkStrategyCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut config = read_config()?;
+
+        let strategy = if self.reset {
+            Some(LinkStrategy::default())
+        } else {
+            self.strategy
+        };
+
+        match strategy {
+            Some(strategy) => {
+                config.link_strategy = strategy;
+                write_config(&config)?;
+                println!("link strategy set to: {strategy}");
+            }
+            None => {
+                println!("current link strategy: {}", config.link_strategy);
+            }
+        }
+
+        Ok(())
+    }
+}
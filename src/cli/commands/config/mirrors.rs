@@ -0,0 +1,60 @@
+use crate::cli::config::{read_config, write_config};
+use clap::Args;
+
+#[derive(Debug, Args)]
+pub struct MirrorsCommand {
+    /// The canonical URL to configure the mirror of, don't pass any value to list the current mirrors
+    #[arg(index = 1, value_parser = crate::cli::parse_gix_url)]
+    url: Option<gix::Url>,
+
+    /// The URL of the mirror to use instead of the canonical URL, don't pass any value to check the current mirror
+    #[arg(index = 2, value_parser = crate::cli::parse_gix_url)]
+    mirror: Option<gix::Url>,
+
+    /// Removes the mirror of the canonical URL
+    #[arg(short, long, requires = "url", conflicts_with = "mirror")]
+    remove: bool,
+}
+
+impl MirrorsCommand {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut config = read_config()?;
+
+        let Some(url) = self.url else {
+            if config.mirrors.is_empty() {
+                println!("no mirrors configured");
+            }
+
+            for (url, mirror) in &config.mirrors.0 {
+                println!("{url} -> {mirror}");
+            }
+
+            return Ok(());
+        };
+
+        if self.remove {
+            if config.mirrors.0.remove(&url).is_some() {
+                write_config(&config)?;
+                println!("removed mirror of {url}");
+            } else {
+                println!("no mirror configured for {url}");
+            }
+
+            return Ok(());
+        }
+
+        match self.mirror {
+            Some(mirror) => {
+                config.mirrors.0.insert(url.clone(), mirror.clone());
+                write_config(&config)?;
+                println!("mirror of {url} set to: {mirror}");
+            }
+            None => match config.mirrors.0.get(&url) {
+                Some(mirror) => println!("current mirror of {url}: {mirror}"),
+                None => println!("no mirror configured for {url}"),
+            },
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use clap::Subcommand;
 
 mod default_index;
+mod link_strategy;
+mod mirrors;
 mod scripts_repo;
 
 #[derive(Debug, Subcommand)]
@@ -8,6 +10,12 @@ pub enum ConfigCommands {
     /// Configuration for the default index
     DefaultIndex(default_index::DefaultIndexCommand),
 
+    /// Configuration for how files are placed from the CAS
+    LinkStrategy(link_strategy::LinkStrategyCommand),
+
+    /// Configuration for the mirrors of indices, repositories and downloads
+    Mirrors(mirrors::MirrorsCommand),
+
     /// Configuration for the scripts repository
     ScriptsRepo(scripts_repo::ScriptsRepoCommand),
 }
@@ -16,6 +24,8 @@ impl ConfigCommands {
     pub fn run(self) -> anyhow::Result<()> {
         match self {
             ConfigCommands::DefaultIndex(default_index) => default_index.run(),
+            ConfigCommands::LinkStrategy(link_strategy) => link_strategy.run(),
+            ConfigCommands::Mirrors(mirrors) => mirrors.run(),
             ConfigCommands::ScriptsRepo(scripts_repo) => scripts_repo.run(),
         }
     }
@@ -37,11 +37,11 @@ impl ExecuteCommand {
             .context("no index specified")?;
         let source = PesdePackageSource::new(index);
         source
-            .refresh(&project)
+            .refresh(&project, &reqwest)
             .context("failed to refresh source")?;
 
         let version_req = self.package.1.unwrap_or(VersionReq::STAR);
-        let Some((version, pkg_ref)) = ('finder: {
+        let Some((version, mut pkg_ref)) = ('finder: {
             let specifier = PesdeDependencySpecifier {
                 name: self.package.0.clone(),
                 version: version_req.clone(),
@@ -50,7 +50,7 @@ impl ExecuteCommand {
             };
 
             if let Some(res) = source
-                .resolve(&specifier, &project, TargetKind::Lune)
+                .resolve(&specifier, &project, &reqwest, TargetKind::Lune)
                 .context("failed to resolve package")?
                 .1
                 .pop_last()
@@ -59,7 +59,7 @@ impl ExecuteCommand {
             }
 
             source
-                .resolve(&specifier, &project, TargetKind::Luau)
+                .resolve(&specifier, &project, &reqwest, TargetKind::Luau)
                 .context("failed to resolve package")?
                 .1
                 .pop_last()
@@ -73,7 +73,7 @@ impl ExecuteCommand {
         log::info!("found package {}@{version}", pkg_ref.name);
 
         let (fs, target) = source
-            .download(&pkg_ref, &project, &reqwest)
+            .download(&mut pkg_ref, &project, &reqwest)
             .context("failed to download package")?;
         let bin_path = target.bin_path().context("package has no binary export")?;
 
@@ -83,7 +83,7 @@ impl ExecuteCommand {
         let tempdir =
             tempfile::tempdir_in(tmp_dir).context("failed to create temporary directory")?;
 
-        fs.write_to(tempdir.path(), project.cas_dir(), true)
+        fs.write_to(tempdir.path(), project.cas_dir(), project.link_strategy())
             .context("failed to write package contents")?;
 
         let mut caller =
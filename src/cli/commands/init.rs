@@ -24,6 +24,53 @@ require(home_dir .. {:?})"#,
     )
 }
 
+/// Writes the default scripts to the project and adds them to the manifest
+pub fn setup_scripts(
+    project: &Project,
+    manifest: &mut toml_edit::DocumentMut,
+) -> anyhow::Result<()> {
+    let folder = project
+        .package_dir()
+        .join(concat!(".", env!("CARGO_PKG_NAME")));
+    std::fs::create_dir_all(&folder).context("failed to create scripts folder")?;
+
+    std::fs::write(
+        folder.join(format!("{}.luau", ScriptName::RobloxSyncConfigGenerator)),
+        script_contents(Path::new(&format!(
+            "lune/rojo/{}.luau",
+            ScriptName::RobloxSyncConfigGenerator
+        ))),
+    )
+    .context("failed to write sync config generator script file")?;
+
+    #[cfg(feature = "wally-compat")]
+    std::fs::write(
+        folder.join(format!("{}.luau", ScriptName::SourcemapGenerator)),
+        script_contents(Path::new(&format!(
+            "lune/rojo/{}.luau",
+            ScriptName::SourcemapGenerator
+        ))),
+    )
+    .context("failed to write sourcemap generator script file")?;
+
+    let scripts = manifest["scripts"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()));
+
+    scripts[&ScriptName::RobloxSyncConfigGenerator.to_string()] = toml_edit::value(format!(
+        concat!(".", env!("CARGO_PKG_NAME"), "/{}.luau"),
+        ScriptName::RobloxSyncConfigGenerator
+    ));
+
+    #[cfg(feature = "wally-compat")]
+    {
+        scripts[&ScriptName::SourcemapGenerator.to_string()] = toml_edit::value(format!(
+            concat!(".", env!("CARGO_PKG_NAME"), "/{}.luau"),
+            ScriptName::SourcemapGenerator
+        ));
+    }
+
+    Ok(())
+}
+
 impl InitCommand {
     pub fn run(self, project: Project) -> anyhow::Result<()> {
         match project.read_manifest() {
@@ -122,46 +169,7 @@ impl InitCommand {
             .prompt()
             .unwrap()
         {
-            let folder = project
-                .package_dir()
-                .join(concat!(".", env!("CARGO_PKG_NAME")));
-            std::fs::create_dir_all(&folder).context("failed to create scripts folder")?;
-
-            std::fs::write(
-                folder.join(format!("{}.luau", ScriptName::RobloxSyncConfigGenerator)),
-                script_contents(Path::new(&format!(
-                    "lune/rojo/{}.luau",
-                    ScriptName::RobloxSyncConfigGenerator
-                ))),
-            )
-            .context("failed to write sync config generator script file")?;
-
-            #[cfg(feature = "wally-compat")]
-            std::fs::write(
-                folder.join(format!("{}.luau", ScriptName::SourcemapGenerator)),
-                script_contents(Path::new(&format!(
-                    "lune/rojo/{}.luau",
-                    ScriptName::SourcemapGenerator
-                ))),
-            )
-            .context("failed to write sourcemap generator script file")?;
-
-            let scripts =
-                manifest["scripts"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()));
-
-            scripts[&ScriptName::RobloxSyncConfigGenerator.to_string()] =
-                toml_edit::value(format!(
-                    concat!(".", env!("CARGO_PKG_NAME"), "/{}.luau"),
-                    ScriptName::RobloxSyncConfigGenerator
-                ));
-
-            #[cfg(feature = "wally-compat")]
-            {
-                scripts[&ScriptName::SourcemapGenerator.to_string()] = toml_edit::value(format!(
-                    concat!(".", env!("CARGO_PKG_NAME"), "/{}.luau"),
-                    ScriptName::SourcemapGenerator
-                ));
-            }
+            setup_scripts(&project, &mut manifest)?;
         }
 
         manifest["indices"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()))
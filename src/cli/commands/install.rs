@@ -1,16 +1,30 @@
 use crate::cli::{
-    bin_dir, download_graph, files::make_executable, run_on_workspace_members, up_to_date_lockfile,
+    bin_dir, download_graph, ensure_not_shared_lockfile_member, files::make_executable,
+    print_graph_diff, register_project, run_on_workspace_members, up_to_date_lockfile,
 };
 use anyhow::Context;
 use clap::Args;
 use colored::{ColoredString, Colorize};
 use indicatif::MultiProgress;
 use pesde::{
-    lockfile::Lockfile,
-    manifest::{target::TargetKind, DependencyType},
-    Project, MANIFEST_FILE_NAME,
+    lock::FileLock,
+    lockfile::{DependencyGraph, DownloadedGraph, Lockfile, CURRENT_FORMAT_VERSION},
+    manifest::{target::TargetKind, DependencyType, Manifest},
+    names::PackageNames,
+    source::{
+        fs::LinkStrategy, refs::PackageRefs, traits::PackageRef, version_id::VersionId,
+        PackageSources,
+    },
+    Project, LOCKFILE_FILE_NAME, MANIFEST_FILE_NAME, PACKAGES_CONTAINER_NAME,
+};
+use relative_path::RelativePathBuf;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    path::{Path, PathBuf},
+    time::SystemTime,
 };
-use std::collections::{BTreeSet, HashSet};
 
 #[derive(Debug, Args, Copy, Clone)]
 pub struct InstallCommand {
@@ -25,6 +39,10 @@ pub struct InstallCommand {
     /// Whether to not install dev dependencies
     #[arg(long)]
     prod: bool,
+
+    /// Whether to check the files being linked against their hashes
+    #[arg(long)]
+    verify: bool,
 }
 
 fn bin_link_file(alias: &str) -> String {
@@ -87,6 +105,471 @@ fn job(n: u8) -> ColoredString {
     format!("[{n}/{JOBS}]").dimmed().bold()
 }
 
+fn strip_downloaded(graph: DownloadedGraph) -> DependencyGraph {
+    graph
+        .into_iter()
+        .map(|(name, versions)| {
+            (
+                name,
+                versions
+                    .into_iter()
+                    .map(|(version, node)| (version, node.node))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+/// What the last install placed into the package folders of a project
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct InstallState {
+    link_strategy: LinkStrategy,
+    packages: BTreeMap<PackageNames, BTreeMap<VersionId, PackageRefs>>,
+    linker_files: BTreeSet<RelativePathBuf>,
+    /// When the state was written, which is when the last install finished
+    #[serde(skip)]
+    installed_at: Option<SystemTime>,
+}
+
+fn install_state_path(project: &Project, manifest: &Manifest) -> PathBuf {
+    let target_kind = manifest.target.kind();
+
+    project
+        .packages_root()
+        .join(target_kind.packages_folder(&target_kind))
+        .join(PACKAGES_CONTAINER_NAME)
+        .join("install_state.toml")
+}
+
+fn read_install_state(
+    project: &Project,
+    manifest: &Manifest,
+) -> anyhow::Result<Option<InstallState>> {
+    let path = install_state_path(project, manifest);
+
+    let state = match std::fs::read_to_string(&path) {
+        Ok(state) => state,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).context("failed to read install state"),
+    };
+
+    match toml::from_str::<InstallState>(&state) {
+        Ok(state) => Ok(Some(InstallState {
+            installed_at: std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+            ..state
+        })),
+        Err(e) => {
+            log::debug!("failed to parse install state, installing from scratch: {e}");
+            Ok(None)
+        }
+    }
+}
+
+fn remove_dir_if_exists(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_dir_all(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn archive_hash(pkg_ref: &mut PackageRefs) -> Option<&mut Option<String>> {
+    match pkg_ref {
+        PackageRefs::Pesde(pkg_ref) => Some(&mut pkg_ref.archive_hash),
+        #[cfg(feature = "wally-compat")]
+        PackageRefs::Wally(pkg_ref) => Some(&mut pkg_ref.archive_hash),
+        _ => None,
+    }
+}
+
+/// Whether two references are of the same package. Archive hashes are only compared if both references record one,
+/// as lockfiles written before they were recorded, and indices which don't publish them, leave them out
+fn same_package(a: &PackageRefs, b: &PackageRefs) -> bool {
+    let (mut a, mut b) = (a.clone(), b.clone());
+
+    if let (Some(a_hash), Some(b_hash)) = (archive_hash(&mut a), archive_hash(&mut b)) {
+        if a_hash.is_none() || b_hash.is_none() {
+            (*a_hash, *b_hash) = (None, None);
+        }
+    }
+
+    a == b
+}
+
+/// Whether the cached file system of a package was removed or written again since it was installed. Both happen when
+/// files it references are found to be corrupted, in which case the installed package may link the corrupted files
+fn cached_fs_changed(
+    project: &Project,
+    pkg_ref: &PackageRefs,
+    installed_at: Option<SystemTime>,
+) -> bool {
+    let Some(path) = project.cached_fs_path(pkg_ref) else {
+        return false;
+    };
+
+    match std::fs::metadata(path).and_then(|metadata| metadata.modified()) {
+        Ok(modified) => installed_at.is_some_and(|installed_at| modified > installed_at),
+        Err(_) => true,
+    }
+}
+
+/// The container folders of the packages installed by the last install which are no longer up to date, relative to
+/// the packages root
+fn outdated_packages(
+    project: &Project,
+    manifest: &Manifest,
+    state: &InstallState,
+    graph: &DependencyGraph,
+    prod: bool,
+) -> BTreeSet<PathBuf> {
+    let link_strategy = manifest.link_strategy.unwrap_or(project.link_strategy());
+    let mut outdated = BTreeSet::new();
+
+    for (name, versions) in &state.packages {
+        for (version_id, pkg_ref) in versions {
+            let up_to_date = state.link_strategy == link_strategy
+                // the contents of these can change without their reference changing
+                && !matches!(pkg_ref, PackageRefs::Path(_) | PackageRefs::Workspace(_))
+                // patches can't be applied twice, so patched packages are always reinstalled
+                && !is_patched(manifest, name, version_id)
+                && !cached_fs_changed(project, pkg_ref, state.installed_at)
+                && graph
+                    .get(name)
+                    .and_then(|versions| versions.get(version_id))
+                    .is_some_and(|node| {
+                        (!prod || node.ty != DependencyType::Dev) && same_package(&node.pkg_ref, pkg_ref)
+                    });
+
+            if up_to_date {
+                continue;
+            }
+
+            log::debug!("{name}@{version_id} is outdated");
+
+            outdated.insert(
+                PathBuf::from(
+                    manifest
+                        .target
+                        .kind()
+                        .packages_folder(&pkg_ref.target_kind()),
+                )
+                .join(PACKAGES_CONTAINER_NAME)
+                .join(name.escaped())
+                .join(version_id.version().to_string())
+                .join(name.as_str().1),
+            );
+        }
+    }
+
+    outdated
+}
+
+/// Removes the container folders of outdated packages, along with their version and name folders once empty
+fn remove_outdated_packages(project: &Project, outdated: &BTreeSet<PathBuf>) -> anyhow::Result<()> {
+    for container_folder in outdated {
+        let container_folder = project.packages_root().join(container_folder);
+
+        remove_dir_if_exists(&container_folder).with_context(|| {
+            format!(
+                "failed to remove outdated package at {}",
+                container_folder.display()
+            )
+        })?;
+
+        // only succeeds if nothing else is left in them
+        for folder in container_folder.ancestors().skip(1).take(2) {
+            let _ = std::fs::remove_dir(folder);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "patches")]
+fn is_patched(manifest: &Manifest, name: &PackageNames, version_id: &VersionId) -> bool {
+    manifest
+        .patches
+        .get(name)
+        .is_some_and(|versions| versions.contains_key(version_id))
+}
+
+#[cfg(not(feature = "patches"))]
+fn is_patched(_manifest: &Manifest, _name: &PackageNames, _version_id: &VersionId) -> bool {
+    false
+}
+
+/// The directory in the project installs are staged in. It is on the same file system as the packages folders, so
+/// staged installs can be moved into place, but outside of them, so file watchers of the packages aren't notified
+fn staging_dir(package_dir: &Path) -> PathBuf {
+    package_dir.join(".pesde")
+}
+
+/// Recreates a directory for staging, leaving out the given paths. Files are reflinked where the file system supports
+/// it, and hard linked otherwise, which is safe since nothing staged is modified in place: outdated and patched
+/// packages and the install state aren't staged, and linking modules are replaced instead of rewritten
+fn stage_dir_all(src: &Path, dst: &Path, skip: &BTreeSet<PathBuf>) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let path = entry.path();
+        if skip.contains(&path) {
+            continue;
+        }
+
+        let ty = entry.file_type()?;
+        let dst = dst.join(entry.file_name());
+
+        if ty.is_dir() {
+            stage_dir_all(&path, &dst, skip)?;
+        } else if ty.is_symlink() {
+            LinkStrategy::Symlink.link(std::fs::read_link(&path)?, dst)?;
+        } else {
+            LinkStrategy::Auto.link(&path, &dst)?;
+            // reflinks and copies don't keep the permissions of the file, such as its executable bits
+            std::fs::set_permissions(&dst, entry.metadata()?.permissions())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// An install written to a staging directory, which is moved into place once every step succeeded
+struct StagedInstall {
+    project: Project,
+    package_dir: PathBuf,
+    dir: tempfile::TempDir,
+    folders: BTreeSet<String>,
+    bin_links: BTreeSet<String>,
+    /// Whether a lockfile was staged, which is moved into place after the packages folders
+    lockfile: bool,
+}
+
+impl StagedInstall {
+    /// Stages an install of the project. If the packages the last install placed are known, every one of them which
+    /// isn't outdated is staged, so only the others need to be written again
+    fn new(
+        project: &Project,
+        manifest: &Manifest,
+        outdated: Option<&BTreeSet<PathBuf>>,
+    ) -> anyhow::Result<Self> {
+        let staging_dir = staging_dir(project.package_dir());
+        std::fs::create_dir_all(&staging_dir).context("failed to create staging directory")?;
+        let dir = tempfile::Builder::new()
+            .prefix("install-")
+            .tempdir_in(&staging_dir)
+            .context("failed to create staging directory")?;
+        let folders = TargetKind::VARIANTS
+            .iter()
+            .map(|target_kind| manifest.target.kind().packages_folder(target_kind))
+            .collect::<BTreeSet<_>>();
+
+        if let Some(outdated) = outdated {
+            let skip = outdated
+                .iter()
+                .map(|container_folder| project.packages_root().join(container_folder))
+                .chain(std::iter::once(install_state_path(project, manifest)))
+                .collect::<BTreeSet<_>>();
+
+            for folder in &folders {
+                let current = project.package_dir().join(folder);
+                if !current.exists() {
+                    continue;
+                }
+
+                log::debug!("staging the {folder} folder");
+
+                stage_dir_all(&current, &dir.path().join(folder), &skip)
+                    .with_context(|| format!("failed to stage the {folder} folder"))?;
+            }
+        }
+
+        Ok(StagedInstall {
+            project: project.clone().with_packages_root(dir.path()),
+            package_dir: project.package_dir().to_path_buf(),
+            dir,
+            folders,
+            bin_links: BTreeSet::new(),
+            lockfile: false,
+        })
+    }
+
+    /// Stages the lockfile of the project, so it is only replaced along with the packages folders
+    fn stage_lockfile(&mut self, lockfile: &Lockfile) -> anyhow::Result<()> {
+        std::fs::write(
+            self.dir.path().join(LOCKFILE_FILE_NAME),
+            toml::to_string(lockfile).context("failed to serialize lockfile")?,
+        )
+        .context("failed to write staged lockfile")?;
+        self.lockfile = true;
+
+        Ok(())
+    }
+
+    /// Replaces the packages folders and the lockfile of the project with the staged ones, then writes the bin links.
+    /// If any of it fails, the folders and lockfile already replaced are restored
+    fn commit(self) -> anyhow::Result<CommittedInstall> {
+        let mut committed = CommittedInstall {
+            package_dir: self.package_dir,
+            dir: self.dir,
+            swapped: vec![],
+        };
+
+        let res = self
+            .folders
+            .iter()
+            .try_for_each(|folder| committed.swap(folder))
+            .and_then(|_| {
+                if self.lockfile {
+                    committed.swap(LOCKFILE_FILE_NAME)
+                } else {
+                    Ok(())
+                }
+            })
+            .and_then(|_| write_bin_links(&self.bin_links));
+
+        match res {
+            Ok(()) => Ok(committed),
+            Err(e) => {
+                committed.rollback();
+                Err(e)
+            }
+        }
+    }
+}
+
+/// A project whose install was staged, with what is needed to finish installing it once the install is committed
+struct StagedProject {
+    install: StagedInstall,
+    project: Project,
+    previous_graph: DownloadedGraph,
+    graph: DownloadedGraph,
+    lock: FileLock,
+}
+
+/// An install whose staged folders and lockfile were moved into place. The ones they replaced are kept until it is
+/// dropped
+struct CommittedInstall {
+    package_dir: PathBuf,
+    dir: tempfile::TempDir,
+    /// The folders and files which were moved out of place, and whether the project had them before
+    swapped: Vec<(String, bool)>,
+}
+
+impl CommittedInstall {
+    fn swap(&mut self, name: &str) -> anyhow::Result<()> {
+        let current = self.package_dir.join(name);
+        let staged = self.dir.path().join(name);
+        let old = self.dir.path().join(".old").join(name);
+        std::fs::create_dir_all(old.parent().unwrap())
+            .context("failed to create directory for old packages")?;
+
+        let existed = match std::fs::rename(&current, &old) {
+            Ok(()) => true,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => false,
+            Err(e) => return Err(e).context(format!("failed to move {name} out of place")),
+        };
+        self.swapped.push((name.to_string(), existed));
+
+        if !staged.exists() {
+            return Ok(());
+        }
+
+        log::debug!("moving the staged {name} into place");
+
+        std::fs::rename(&staged, &current)
+            .with_context(|| format!("failed to move the staged {name} into place"))
+    }
+
+    /// Restores the folders and files the project had before, in the reverse order they were replaced in
+    fn rollback(self) {
+        let mut restored = true;
+
+        for (name, existed) in self.swapped.iter().rev() {
+            let current = self.package_dir.join(name);
+            let _ = std::fs::rename(&current, self.dir.path().join(name));
+
+            if !existed {
+                continue;
+            }
+
+            let old = self.dir.path().join(".old").join(name);
+            if let Err(e) = std::fs::rename(&old, &current) {
+                log::error!(
+                    "failed to restore {name}, it can be found at {}: {e}",
+                    old.display()
+                );
+                restored = false;
+            }
+        }
+
+        // the ones which couldn't be restored mustn't be removed along with the staging directory
+        if !restored {
+            let _ = self.dir.into_path();
+        }
+    }
+}
+
+fn write_bin_links(aliases: &BTreeSet<String>) -> anyhow::Result<()> {
+    if aliases.is_empty() {
+        return Ok(());
+    }
+
+    let bin_folder = bin_dir()?;
+
+    for alias in aliases {
+        let bin_file = bin_folder.join(alias);
+        std::fs::write(&bin_file, bin_link_file(alias)).context("failed to write bin link file")?;
+
+        make_executable(&bin_file).context("failed to make bin link executable")?;
+
+        #[cfg(windows)]
+        {
+            let bin_file = bin_file.with_extension(std::env::consts::EXE_EXTENSION);
+            std::fs::copy(
+                std::env::current_exe().context("failed to get current executable path")?,
+                &bin_file,
+            )
+            .context("failed to copy bin link file")?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves the staged installs into place, along with the lockfiles staged in them. If any of it fails, the installs
+/// already moved into place are rolled back
+fn commit_installs(staged_installs: Vec<StagedInstall>) -> anyhow::Result<()> {
+    let mut committed = vec![];
+
+    let res = staged_installs.into_iter().try_for_each(|staged| {
+        committed.push(staged.commit()?);
+        Ok(())
+    });
+
+    if let Err(e) = res {
+        for committed in committed.into_iter().rev() {
+            committed.rollback();
+        }
+
+        return Err(e);
+    }
+
+    let staging_dirs = committed
+        .iter()
+        .map(|committed| staging_dir(&committed.package_dir))
+        .collect::<BTreeSet<_>>();
+    drop(committed);
+
+    // only succeeds if no other install is being staged
+    for staging_dir in staging_dirs {
+        let _ = std::fs::remove_dir(staging_dir);
+    }
+
+    Ok(())
+}
+
 impl InstallCommand {
     pub fn run(
         self,
@@ -100,101 +583,332 @@ impl InstallCommand {
             .deser_manifest()
             .context("failed to read manifest")?;
 
-        let lockfile = if self.locked {
-            match up_to_date_lockfile(&project)? {
+        ensure_not_shared_lockfile_member(&project, "install")?;
+
+        if manifest.shared_lockfile {
+            let _lock = project
+                .lock_path(project.package_dir())
+                .context("failed to lock project")?;
+
+            let lockfile = self.read_lockfile(&project, &manifest)?;
+
+            return self.run_shared(
+                project,
+                manifest,
+                lockfile,
+                &mut refreshed_sources,
+                multi,
+                reqwest,
+            );
+        }
+
+        let staged_projects = self.stage(project, &mut refreshed_sources, &multi, &reqwest)?;
+
+        println!("{} 🧹 finishing up", job(JOBS));
+
+        let mut staged_installs = vec![];
+        let mut finished = vec![];
+        for staged in staged_projects {
+            staged_installs.push(staged.install);
+            finished.push((
+                staged.project,
+                staged.previous_graph,
+                staged.graph,
+                staged.lock,
+            ));
+        }
+
+        // the installs are only moved into place once the project and every workspace member were staged successfully
+        commit_installs(staged_installs)?;
+
+        for (project, previous_graph, graph, _lock) in finished {
+            register_project(&project);
+
+            print_graph_diff(&previous_graph, &graph);
+        }
+
+        Ok(())
+    }
+
+    /// Stages the install of the project and its workspace members, the latter coming first
+    fn stage(
+        &self,
+        project: Project,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        multi: &MultiProgress,
+        reqwest: &reqwest::blocking::Client,
+    ) -> anyhow::Result<Vec<StagedProject>> {
+        let manifest = project
+            .deser_manifest()
+            .context("failed to read manifest")?;
+
+        let lock = project
+            .lock_path(project.package_dir())
+            .context("failed to lock project")?;
+
+        let lockfile = self.read_lockfile(&project, &manifest)?;
+
+        println!(
+            "\n{}\n",
+            format!("[now installing {} {}]", manifest.name, manifest.target)
+                .bold()
+                .on_bright_black()
+        );
+
+        let previous_graph = lockfile
+            .as_ref()
+            .map(|lockfile| lockfile.graph.clone())
+            .unwrap_or_default();
+
+        let old_graph = lockfile.map(|lockfile| strip_downloaded(lockfile.graph));
+
+        println!("{} 📦 building dependency graph", job(1));
+
+        let graph = project
+            .dependency_graph(old_graph.as_ref(), refreshed_sources, reqwest)
+            .context("failed to build dependency graph")?;
+
+        let (downloaded_graph, mut staged) = self.install_graph(
+            &project,
+            &manifest,
+            &graph,
+            refreshed_sources,
+            multi,
+            reqwest,
+        )?;
+
+        let refreshed_sources = RefCell::new(refreshed_sources);
+        let staged_members = RefCell::new(vec![]);
+        let workspace = run_on_workspace_members(&project, |member| {
+            let staged = self.stage(member, &mut refreshed_sources.borrow_mut(), multi, reqwest)?;
+            staged_members.borrow_mut().extend(staged);
+            Ok(())
+        })?;
+
+        let lockfile = Lockfile {
+            format_version: CURRENT_FORMAT_VERSION,
+            name: manifest.name,
+            version: manifest.version,
+            target: manifest.target.kind(),
+            overrides: manifest.overrides,
+
+            graph: downloaded_graph,
+            members: Default::default(),
+
+            workspace,
+        };
+        staged.stage_lockfile(&lockfile)?;
+
+        let mut staged_projects = staged_members.into_inner();
+        staged_projects.push(StagedProject {
+            install: staged,
+            project,
+            previous_graph,
+            graph: lockfile.graph,
+            lock,
+        });
+
+        Ok(staged_projects)
+    }
+
+    fn read_lockfile(
+        &self,
+        project: &Project,
+        manifest: &Manifest,
+    ) -> anyhow::Result<Option<Lockfile>> {
+        if self.locked {
+            return match up_to_date_lockfile(project)? {
                 None => {
                     anyhow::bail!(
                         "lockfile is out of sync, run `{} install` to update it",
                         env!("CARGO_BIN_NAME")
                     );
                 }
-                file => file,
-            }
-        } else {
-            match project.deser_lockfile() {
-                Ok(lockfile) => {
-                    if lockfile.overrides != manifest.overrides {
-                        log::debug!("overrides are different");
-                        None
-                    } else if lockfile.target != manifest.target.kind() {
-                        log::debug!("target kind is different");
-                        None
-                    } else {
-                        Some(lockfile)
-                    }
-                }
-                Err(pesde::errors::LockfileReadError::Io(e))
-                    if e.kind() == std::io::ErrorKind::NotFound =>
-                {
-                    None
+                file => Ok(file),
+            };
+        }
+
+        match project.deser_lockfile() {
+            Ok(lockfile) => {
+                if lockfile.overrides != manifest.overrides {
+                    log::debug!("overrides are different");
+                    Ok(None)
+                } else if lockfile.target != manifest.target.kind() {
+                    log::debug!("target kind is different");
+                    Ok(None)
+                } else {
+                    Ok(Some(lockfile))
                 }
-                Err(e) => return Err(e.into()),
             }
-        };
+            Err(pesde::errors::LockfileReadError::Io(e))
+                if e.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
 
+    fn run_shared(
+        self,
+        project: Project,
+        manifest: Manifest,
+        lockfile: Option<Lockfile>,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        multi: MultiProgress,
+        reqwest: reqwest::blocking::Client,
+    ) -> anyhow::Result<()> {
         println!(
             "\n{}\n",
-            format!("[now installing {} {}]", manifest.name, manifest.target)
-                .bold()
-                .on_bright_black()
+            format!(
+                "[now installing {} {} workspace]",
+                manifest.name, manifest.target
+            )
+            .bold()
+            .on_bright_black()
         );
 
-        println!("{} ❌ removing current package folders", job(1));
+        let mut members = project
+            .workspace_members(project.package_dir())
+            .context("failed to get workspace members")?
+            .into_iter()
+            .map(|(path, manifest)| {
+                let relative =
+                    RelativePathBuf::from_path(path.strip_prefix(project.package_dir()).unwrap())
+                        .unwrap();
+                (relative, (project.for_member(path), manifest))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let workspace = members.iter().fold(
+            BTreeMap::<_, BTreeMap<_, _>>::new(),
+            |mut map, (path, (_, manifest))| {
+                map.entry(manifest.name.clone())
+                    .or_default()
+                    .insert(manifest.target.kind(), path.clone());
+                map
+            },
+        );
+        members.insert(RelativePathBuf::new(), (project.clone(), manifest.clone()));
 
-        {
-            let mut deleted_folders = HashSet::new();
+        let old_graphs = lockfile
+            .as_ref()
+            .map(|lockfile| {
+                lockfile
+                    .members
+                    .keys()
+                    .filter_map(|path| {
+                        lockfile
+                            .member_graph(path)
+                            .map(|graph| (path.clone(), strip_downloaded(graph)))
+                    })
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default();
+        let previous_graph = lockfile.map(|lockfile| lockfile.graph).unwrap_or_default();
 
-            for target_kind in TargetKind::VARIANTS {
-                let folder = manifest.target.kind().packages_folder(target_kind);
+        println!("{} 📦 building dependency graph", job(1));
 
-                if deleted_folders.insert(folder.to_string()) {
-                    log::debug!("deleting the {folder} folder");
+        let graphs = project
+            .workspace_dependency_graph(&old_graphs, refreshed_sources, &reqwest)
+            .context("failed to build dependency graph")?;
 
-                    if let Some(e) = std::fs::remove_dir_all(project.package_dir().join(&folder))
-                        .err()
-                        .filter(|e| e.kind() != std::io::ErrorKind::NotFound)
-                    {
-                        return Err(e).context(format!("failed to remove the {folder} folder"));
-                    };
-                }
-            }
+        let mut downloaded_graphs = BTreeMap::new();
+        let mut staged_installs = vec![];
+
+        for (path, graph) in graphs {
+            let (member, member_manifest) = &members[&path];
+
+            println!(
+                "\n{}\n",
+                format!(
+                    "[now installing {} {}]",
+                    member_manifest.name, member_manifest.target
+                )
+                .bold()
+                .on_bright_black()
+            );
+
+            let (downloaded_graph, staged) = self.install_graph(
+                member,
+                member_manifest,
+                &graph,
+                refreshed_sources,
+                &multi,
+                &reqwest,
+            )?;
+            downloaded_graphs.insert(path.clone(), downloaded_graph);
+            staged_installs.push(staged);
         }
 
-        let old_graph = lockfile.map(|lockfile| {
-            lockfile
-                .graph
-                .into_iter()
-                .map(|(name, versions)| {
-                    (
-                        name,
-                        versions
-                            .into_iter()
-                            .map(|(version, node)| (version, node.node))
-                            .collect(),
-                    )
-                })
-                .collect()
-        });
+        let (graph, members) = Lockfile::combine_member_graphs(downloaded_graphs);
 
-        println!("{} 📦 building dependency graph", job(2));
+        let lockfile = Lockfile {
+            format_version: CURRENT_FORMAT_VERSION,
+            name: manifest.name,
+            version: manifest.version,
+            target: manifest.target.kind(),
+            overrides: manifest.overrides,
 
-        let graph = project
-            .dependency_graph(old_graph.as_ref(), &mut refreshed_sources)
-            .context("failed to build dependency graph")?;
+            graph,
+            members,
+
+            workspace,
+        };
+
+        println!("{} 🧹 finishing up", job(JOBS));
+
+        staged_installs
+            .iter_mut()
+            .find(|staged| staged.package_dir == project.package_dir())
+            .context("workspace root wasn't staged")?
+            .stage_lockfile(&lockfile)?;
+
+        // the installs are only moved into place once every member has been installed successfully
+        commit_installs(staged_installs)?;
+        register_project(&project);
+
+        print_graph_diff(&previous_graph, &lockfile.graph);
+
+        Ok(())
+    }
+
+    fn install_graph(
+        &self,
+        project: &Project,
+        manifest: &Manifest,
+        graph: &DependencyGraph,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        multi: &MultiProgress,
+        reqwest: &reqwest::blocking::Client,
+    ) -> anyhow::Result<(DownloadedGraph, StagedInstall)> {
+        println!("{} ❌ removing outdated packages", job(2));
+
+        let state = read_install_state(project, manifest)?;
+        let outdated = state
+            .as_ref()
+            .map(|state| outdated_packages(project, manifest, state, graph, self.prod));
+        let staged = StagedInstall::new(project, manifest, outdated.as_ref())?;
+        let project = &staged.project;
+
+        if let Some(outdated) = &outdated {
+            remove_outdated_packages(project, outdated)?;
+        }
 
         let downloaded_graph = download_graph(
-            &project,
-            &mut refreshed_sources,
-            &graph,
-            &multi,
-            &reqwest,
+            project,
+            refreshed_sources,
+            graph,
+            multi,
+            reqwest,
             self.threads as usize,
             self.prod,
             true,
+            self.verify,
+            // the packages which aren't outdated were staged
+            true,
             format!("{} 📥 downloading dependencies", job(3)),
             format!("{} 📥 downloaded dependencies", job(3)),
         )?;
-
         let filtered_graph = if self.prod {
             downloaded_graph
                 .clone()
@@ -214,11 +928,11 @@ impl InstallCommand {
 
         println!("{} 🗺️ linking dependencies", job(4));
 
-        project
+        let linker_files = project
             .link_dependencies(&filtered_graph)
             .context("failed to link dependencies")?;
 
-        let bin_folder = bin_dir()?;
+        let mut bin_links = BTreeSet::new();
 
         for versions in filtered_graph.values() {
             for node in versions.values() {
@@ -235,21 +949,8 @@ impl InstallCommand {
                     continue;
                 }
 
-                let bin_file = bin_folder.join(alias);
-                std::fs::write(&bin_file, bin_link_file(alias))
-                    .context("failed to write bin link file")?;
-
-                make_executable(&bin_file).context("failed to make bin link executable")?;
-
-                #[cfg(windows)]
-                {
-                    let bin_file = bin_file.with_extension(std::env::consts::EXE_EXTENSION);
-                    std::fs::copy(
-                        std::env::current_exe().context("failed to get current executable path")?,
-                        &bin_file,
-                    )
-                    .context("failed to copy bin link file")?;
-                }
+                // written once the install is moved into place
+                bin_links.insert(alias.clone());
             }
         }
 
@@ -262,23 +963,299 @@ impl InstallCommand {
                 .context("failed to apply patches")?;
         }
 
-        println!("{} 🧹 finishing up", job(JOBS));
+        let packages_root = project
+            .packages_root()
+            .canonicalize()
+            .context("failed to canonicalize staging directory")?;
+        let linker_files = linker_files
+            .into_iter()
+            .filter_map(|path| {
+                RelativePathBuf::from_path(path.strip_prefix(&packages_root).ok()?).ok()
+            })
+            .collect::<BTreeSet<_>>();
 
-        project
-            .write_lockfile(Lockfile {
-                name: manifest.name,
-                version: manifest.version,
-                target: manifest.target.kind(),
-                overrides: manifest.overrides,
+        if let Some(state) = state {
+            for path in state.linker_files.difference(&linker_files) {
+                if !path
+                    .components()
+                    .all(|component| matches!(component, relative_path::Component::Normal(_)))
+                {
+                    continue;
+                }
 
-                graph: downloaded_graph,
+                log::debug!("removing outdated linking module {path}");
 
-                workspace: run_on_workspace_members(&project, |project| {
-                    self.run(project, multi.clone(), reqwest.clone())
-                })?,
-            })
-            .context("failed to write lockfile")?;
+                if let Some(e) = std::fs::remove_file(path.to_path(project.packages_root()))
+                    .err()
+                    .filter(|e| e.kind() != std::io::ErrorKind::NotFound)
+                {
+                    return Err(e).context(format!("failed to remove linking module {path}"));
+                }
+            }
+        }
 
-        Ok(())
+        let state = InstallState {
+            link_strategy: manifest.link_strategy.unwrap_or(project.link_strategy()),
+            packages: filtered_graph
+                .iter()
+                .map(|(name, versions)| {
+                    (
+                        name.clone(),
+                        versions
+                            .iter()
+                            .map(|(version_id, node)| {
+                                (version_id.clone(), node.node.pkg_ref.clone())
+                            })
+                            .collect(),
+                    )
+                })
+                .collect(),
+            linker_files,
+            installed_at: None,
+        };
+        let state_path = install_state_path(project, manifest);
+        std::fs::create_dir_all(state_path.parent().unwrap())
+            .context("failed to create package container folder")?;
+        std::fs::write(
+            &state_path,
+            toml::to_string(&state).context("failed to serialize install state")?,
+        )
+        .context("failed to write install state")?;
+
+        Ok((
+            downloaded_graph,
+            StagedInstall {
+                bin_links,
+                ..staged
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pesde::AuthConfig;
+
+    fn staged_install(package_dir: &Path, folders: &[&str]) -> StagedInstall {
+        let project = Project::new(
+            package_dir,
+            None::<PathBuf>,
+            package_dir.join("data"),
+            package_dir.join("cas"),
+            AuthConfig::default(),
+        );
+        let staging_dir = staging_dir(package_dir);
+        std::fs::create_dir_all(&staging_dir).unwrap();
+
+        StagedInstall {
+            project,
+            package_dir: package_dir.to_path_buf(),
+            dir: tempfile::tempdir_in(staging_dir).unwrap(),
+            folders: folders.iter().map(ToString::to_string).collect(),
+            bin_links: BTreeSet::new(),
+            lockfile: false,
+        }
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    fn read(path: &Path) -> String {
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn moves_staged_folders_into_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = staged_install(dir.path(), &["luau_packages", "roblox_packages"]);
+        write(&dir.path().join("luau_packages/old.luau"), "old");
+        write(&staged.dir.path().join("luau_packages/new.luau"), "new");
+
+        drop(staged.commit().unwrap());
+
+        assert!(!dir.path().join("luau_packages/old.luau").exists());
+        assert_eq!(read(&dir.path().join("luau_packages/new.luau")), "new");
+        assert!(!dir.path().join("roblox_packages").exists());
+    }
+
+    #[test]
+    fn restores_every_folder_when_a_later_one_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = staged_install(dir.path(), &["luau_packages", "roblox_packages"]);
+        for folder in ["luau_packages", "roblox_packages"] {
+            write(&dir.path().join(folder).join("init.luau"), "old");
+            write(&staged.dir.path().join(folder).join("init.luau"), "new");
+        }
+        // the second folder can't be moved out of place, as something already occupies its destination
+        write(
+            &staged.dir.path().join(".old/roblox_packages/init.luau"),
+            "occupied",
+        );
+
+        assert!(staged.commit().is_err());
+
+        for folder in ["luau_packages", "roblox_packages"] {
+            assert_eq!(read(&dir.path().join(folder).join("init.luau")), "old");
+        }
+    }
+
+    #[test]
+    fn rolls_back_committed_installs() {
+        let dir = tempfile::tempdir().unwrap();
+        let staged = staged_install(dir.path(), &["luau_packages", "roblox_packages"]);
+        write(&dir.path().join("luau_packages/init.luau"), "old");
+        write(&staged.dir.path().join("luau_packages/init.luau"), "new");
+        write(&staged.dir.path().join("roblox_packages/init.luau"), "new");
+
+        let committed = staged.commit().unwrap();
+        assert_eq!(read(&dir.path().join("luau_packages/init.luau")), "new");
+        committed.rollback();
+
+        assert_eq!(read(&dir.path().join("luau_packages/init.luau")), "old");
+        assert!(!dir.path().join("roblox_packages").exists());
+    }
+
+    #[test]
+    fn rolls_back_workspace_members_when_the_root_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let member_dir = dir.path().join("member");
+        let lockfile = Lockfile {
+            format_version: CURRENT_FORMAT_VERSION,
+            name: "acme/app".parse().unwrap(),
+            version: "0.1.0".parse().unwrap(),
+            target: TargetKind::Luau,
+            overrides: Default::default(),
+
+            graph: Default::default(),
+            members: Default::default(),
+
+            workspace: Default::default(),
+        };
+
+        let mut staged_installs = vec![];
+        for package_dir in [member_dir.as_path(), dir.path()] {
+            let mut staged = staged_install(package_dir, &["luau_packages"]);
+            write(&package_dir.join("luau_packages/init.luau"), "old");
+            write(&package_dir.join(LOCKFILE_FILE_NAME), "old");
+            write(&staged.dir.path().join("luau_packages/init.luau"), "new");
+            staged.stage_lockfile(&lockfile).unwrap();
+            staged_installs.push(staged);
+        }
+        // the root's lockfile can't be moved out of place, as something already occupies its destination
+        write(
+            &staged_installs[1]
+                .dir
+                .path()
+                .join(".old")
+                .join(LOCKFILE_FILE_NAME)
+                .join("init.luau"),
+            "occupied",
+        );
+
+        assert!(commit_installs(staged_installs).is_err());
+
+        for package_dir in [member_dir.as_path(), dir.path()] {
+            assert_eq!(read(&package_dir.join("luau_packages/init.luau")), "old");
+            assert_eq!(read(&package_dir.join(LOCKFILE_FILE_NAME)), "old");
+        }
+    }
+
+    #[test]
+    fn leaves_out_outdated_packages_when_staging() {
+        let dir = tempfile::tempdir().unwrap();
+        let current = dir.path().join("luau_packages");
+        write(&current.join(".pesde/acme+foo/0.1.0/foo/init.luau"), "foo");
+        write(&current.join(".pesde/acme+bar/0.1.0/bar/init.luau"), "bar");
+        write(&current.join("bar.luau"), "return 'bar'");
+
+        let staged = dir.path().join("staged");
+        stage_dir_all(
+            &current,
+            &staged,
+            &BTreeSet::from([current.join(".pesde/acme+foo/0.1.0/foo")]),
+        )
+        .unwrap();
+
+        assert!(!staged.join(".pesde/acme+foo/0.1.0/foo").exists());
+        assert_eq!(
+            read(&staged.join(".pesde/acme+bar/0.1.0/bar/init.luau")),
+            "bar"
+        );
+        assert_eq!(read(&staged.join("bar.luau")), "return 'bar'");
+    }
+
+    #[test]
+    fn reinstalls_packages_whose_files_were_corrupted() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = staged_install(dir.path(), &[]).project;
+        let manifest = toml::from_str::<Manifest>(
+            "name = \"acme/app\"\nversion = \"0.1.0\"\n\n[target]\nenvironment = \"luau\"\n\n[scripts]\n",
+        )
+        .unwrap();
+        let pkg_ref = toml::from_str::<PackageRefs>(
+            "ref_ty = \"pesde\"\nname = \"acme/foo\"\nversion = \"0.1.0\"\nindex_url = \"file:///index\"\n\n[target]\nenvironment = \"luau\"\n",
+        )
+        .unwrap();
+        let name = PackageNames::Pesde("acme/foo".parse().unwrap());
+        let version_id = "0.1.0 luau".parse::<VersionId>().unwrap();
+        let graph = DependencyGraph::from([(
+            name.clone(),
+            BTreeMap::from([(
+                version_id.clone(),
+                pesde::lockfile::DependencyGraphNode {
+                    direct: None,
+                    dependencies: Default::default(),
+                    ty: DependencyType::Standard,
+                    pkg_ref: pkg_ref.clone(),
+                },
+            )]),
+        )]);
+
+        // a file in the cas whose contents don't match its hash, referenced by the package's cached file system
+        let hash = "ab".repeat(32);
+        write(
+            &project.cas_dir().join(&hash[..2]).join(&hash[2..]),
+            "corrupted",
+        );
+        let cached_fs = project.cached_fs_path(&pkg_ref).unwrap();
+        let write_cached_fs = || {
+            let fs = pesde::source::fs::PackageFS::CAS(BTreeMap::from([(
+                RelativePathBuf::from("init.luau"),
+                pesde::source::fs::FSEntry::File(hash.clone(), None),
+            )]));
+            write(&cached_fs, &toml::to_string(&fs).unwrap());
+        };
+        write_cached_fs();
+
+        let installed_at = std::fs::metadata(&cached_fs).unwrap().modified().unwrap();
+        let state = InstallState {
+            link_strategy: project.link_strategy(),
+            packages: BTreeMap::from([(name, BTreeMap::from([(version_id, pkg_ref)]))]),
+            linker_files: BTreeSet::new(),
+            installed_at: Some(installed_at),
+        };
+        let outdated = || outdated_packages(&project, &manifest, &state, &graph, false);
+        assert!(outdated().is_empty());
+
+        let report = project.verify_cas().unwrap();
+        assert_eq!(report.invalidated, std::slice::from_ref(&cached_fs));
+        assert_eq!(
+            outdated(),
+            BTreeSet::from([PathBuf::from("luau_packages/.pesde/acme+foo/0.1.0/foo")])
+        );
+
+        // downloading the package again writes its cached file system anew, so it is still outdated afterwards.
+        // file times can be coarser than the clock, so its time is set explicitly
+        write_cached_fs();
+        std::fs::File::options()
+            .write(true)
+            .open(&cached_fs)
+            .unwrap()
+            .set_modified(installed_at + std::time::Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(outdated().len(), 1);
     }
 }
@@ -0,0 +1,56 @@
+use crate::cli::print_graph_diff;
+use anyhow::Context;
+use clap::Args;
+use pesde::{lockfile::Lockfile, Project, LOCKFILE_FILE_NAME};
+use relative_path::RelativePathBuf;
+
+#[derive(Debug, Args)]
+pub struct DiffCommand {
+    /// The git revision to compare the current lockfile with
+    #[arg(index = 1, default_value = "HEAD")]
+    rev: String,
+}
+
+impl DiffCommand {
+    pub fn run(self, project: Project) -> anyhow::Result<()> {
+        let repo = gix::discover(project.package_dir()).context("failed to find git repository")?;
+        let work_dir = repo
+            .work_dir()
+            .context("git repository has no working directory")?
+            .canonicalize()
+            .context("failed to canonicalize working directory")?;
+
+        let lockfile_path = project
+            .package_dir()
+            .canonicalize()
+            .context("failed to canonicalize package directory")?
+            .join(LOCKFILE_FILE_NAME);
+        let lockfile_path = RelativePathBuf::from_path(
+            lockfile_path
+                .strip_prefix(&work_dir)
+                .context("lockfile is not inside the git repository")?,
+        )
+        .context("failed to make lockfile path relative")?;
+
+        let spec = format!("{}:{lockfile_path}", self.rev);
+        let blob = repo
+            .rev_parse_single(spec.as_str())
+            .with_context(|| format!("failed to find lockfile at {}", self.rev))?
+            .object()
+            .context("failed to read lockfile object")?;
+        let old = std::str::from_utf8(&blob.data)
+            .context("lockfile is not valid utf-8")?
+            .parse::<Lockfile>()
+            .with_context(|| format!("failed to parse lockfile at {}", self.rev))?;
+
+        let current = project
+            .deser_lockfile()
+            .context("failed to read lockfile")?;
+
+        if !print_graph_diff(&old.graph, &current.graph) {
+            println!("no changes to the lockfile since {}", self.rev);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+use clap::Subcommand;
+use pesde::Project;
+
+mod diff;
+
+#[derive(Debug, Subcommand)]
+pub enum LockCommands {
+    /// Shows the changes to the lockfile since a git revision
+    Diff(diff::DiffCommand),
+}
+
+impl LockCommands {
+    pub fn run(self, project: Project) -> anyhow::Result<()> {
+        match self {
+            LockCommands::Diff(diff) => diff.run(project),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use clap::Subcommand;
+use indicatif::MultiProgress;
+use pesde::Project;
+
+mod wally;
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommands {
+    /// Converts a Wally project into a pesde project
+    Wally(wally::WallyCommand),
+}
+
+impl MigrateCommands {
+    pub fn run(
+        self,
+        project: Project,
+        multi: MultiProgress,
+        reqwest: reqwest::blocking::Client,
+    ) -> anyhow::Result<()> {
+        match self {
+            MigrateCommands::Wally(wally) => wally.run(project, multi, reqwest),
+        }
+    }
+}
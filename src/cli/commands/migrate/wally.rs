@@ -0,0 +1,439 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+};
+
+use anyhow::Context;
+use clap::Args;
+use colored::Colorize;
+use indicatif::MultiProgress;
+use relative_path::RelativePathBuf;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::cli::{
+    commands::init::setup_scripts, config::read_config, download_graph, register_project,
+};
+use pesde::{
+    errors::ManifestReadError,
+    lockfile::{DependencyGraph, DependencyGraphNode, Lockfile, CURRENT_FORMAT_VERSION},
+    manifest::{
+        target::{RobloxPlaceKind, TargetKind},
+        DependencyType,
+    },
+    names::{wally::WallyPackageName, PackageName, PackageNames},
+    source::{
+        pesde::{specifier::PesdeDependencySpecifier, PesdePackageSource},
+        refs::PackageRefs,
+        specifiers::DependencySpecifiers,
+        traits::PackageSource,
+        version_id::VersionId,
+        wally::{
+            manifest::{Realm, WallyManifest, WALLY_MANIFEST_FILE_NAME},
+            specifier::WallyDependencySpecifier,
+            WallyPackageSource,
+        },
+    },
+    Project, DEFAULT_INDEX_NAME,
+};
+
+const WALLY_LOCKFILE_FILE_NAME: &str = "wally.lock";
+
+#[derive(Debug, Args)]
+pub struct WallyCommand {
+    /// The amount of threads to use for downloading the dependencies locked by the Wally lockfile
+    #[arg(short, long, default_value_t = 6, value_parser = clap::value_parser!(u64).range(1..=128))]
+    threads: u64,
+
+    /// Use packages from the pesde index in place of Wally packages with the same name, where it has versions matching their requirements
+    #[arg(long)]
+    prefer_pesde: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct WallyLockfile {
+    #[serde(default, rename = "package")]
+    packages: Vec<WallyLockfilePackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WallyLockfilePackage {
+    name: WallyPackageName,
+    version: Version,
+    /// The dependencies of the package, as pairs of their alias and `name@version`
+    #[serde(default)]
+    dependencies: Vec<(String, String)>,
+}
+
+fn parse_locked_dependency(dependency: &str) -> anyhow::Result<(WallyPackageName, Version)> {
+    let (name, version) = dependency
+        .split_once('@')
+        .with_context(|| format!("invalid locked dependency {dependency}"))?;
+
+    Ok((
+        name.parse()
+            .with_context(|| format!("invalid locked dependency name {name}"))?,
+        version
+            .parse()
+            .with_context(|| format!("invalid locked dependency version {version}"))?,
+    ))
+}
+
+/// The pesde name for a Wally package name, if it can be expressed as one
+fn pesde_name(name: &WallyPackageName) -> Option<PackageName> {
+    let (scope, name) = name.as_str();
+
+    format!("{scope}/{name}")
+        .replace('-', "_")
+        .to_lowercase()
+        .parse()
+        .ok()
+}
+
+/// Finds the lib export file from the path the Rojo project maps its tree to
+fn find_lib(package_dir: &Path) -> Option<(RelativePathBuf, String)> {
+    let project = std::fs::read_to_string(package_dir.join("default.project.json")).ok()?;
+    let project: serde_json::Value = serde_json::from_str(&project).ok()?;
+    let tree_path = project["tree"]["$path"].as_str()?;
+
+    let path = RelativePathBuf::from(tree_path);
+    if path
+        .extension()
+        .is_some_and(|ext| ext == "lua" || ext == "luau")
+    {
+        return Some((path, tree_path.to_string()));
+    }
+
+    ["init.luau", "init.lua"]
+        .into_iter()
+        .map(|file| path.join(file))
+        .find(|file| file.to_path(package_dir).is_file())
+        .map(|file| (file, tree_path.to_string()))
+}
+
+impl WallyCommand {
+    pub fn run(
+        self,
+        project: Project,
+        multi: MultiProgress,
+        reqwest: reqwest::blocking::Client,
+    ) -> anyhow::Result<()> {
+        match project.read_manifest() {
+            Ok(_) => {
+                println!("{}", "project already initialized".red());
+                return Ok(());
+            }
+            Err(ManifestReadError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        };
+
+        let wally_manifest =
+            std::fs::read_to_string(project.package_dir().join(WALLY_MANIFEST_FILE_NAME))
+                .context("failed to read wally manifest")?;
+        let wally_manifest: WallyManifest =
+            toml::from_str(&wally_manifest).context("failed to parse wally manifest")?;
+        // aliases have to be unique across all of a pesde package's dependencies, so shared and server dependencies
+        // can't use the same ones
+        wally_manifest
+            .all_dependencies()
+            .context("failed to migrate wally dependencies")?;
+
+        let name = pesde_name(&wally_manifest.package.name).with_context(|| {
+            format!(
+                "{} can't be used as a pesde package name",
+                wally_manifest.package.name
+            )
+        })?;
+        let target_kind = match wally_manifest.package.realm {
+            Realm::Shared => TargetKind::Roblox,
+            Realm::Server => TargetKind::RobloxServer,
+        };
+
+        let mut manifest = toml_edit::DocumentMut::new();
+
+        manifest["name"] = toml_edit::value(name.to_string());
+        manifest["version"] = toml_edit::value(wally_manifest.package.version.to_string());
+
+        if let Some(description) = &wally_manifest.package.description {
+            manifest["description"] = toml_edit::value(description);
+        }
+
+        if let Some(license) = &wally_manifest.package.license {
+            manifest["license"] = toml_edit::value(license);
+        }
+
+        if !wally_manifest.package.authors.is_empty() {
+            manifest["authors"] = toml_edit::value(
+                wally_manifest
+                    .package
+                    .authors
+                    .iter()
+                    .collect::<toml_edit::Array>(),
+            );
+        }
+
+        if wally_manifest.package.private {
+            manifest["private"] = toml_edit::value(true);
+        }
+
+        let target = manifest["target"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()));
+        target["environment"] = toml_edit::value(target_kind.to_string());
+
+        match find_lib(project.package_dir()) {
+            Some((lib, build_file)) => {
+                target["lib"] = toml_edit::value(lib.to_string());
+                target["build_files"] = toml_edit::value(toml_edit::Array::from_iter([build_file]));
+            }
+            None => println!(
+                "{}",
+                "couldn't find the lib export file from default.project.json, set it in the target manually"
+                    .yellow()
+            ),
+        }
+
+        for (kind, place) in [
+            (
+                RobloxPlaceKind::Shared,
+                &wally_manifest.place.shared_packages,
+            ),
+            (
+                RobloxPlaceKind::Server,
+                &wally_manifest.place.server_packages,
+            ),
+        ] {
+            if let Some(place) = place {
+                manifest["place"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()))
+                    [&kind.to_string()] = toml_edit::value(place);
+            }
+        }
+
+        setup_scripts(&project, &mut manifest)?;
+
+        let pesde_index = read_config()?.default_index;
+        manifest["indices"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()))
+            [DEFAULT_INDEX_NAME] = toml_edit::value(pesde_index.to_bstring().to_string());
+        manifest["wally_indices"].or_insert(toml_edit::Item::Table(toml_edit::Table::new()))
+            [DEFAULT_INDEX_NAME] = toml_edit::value(wally_manifest.package.registry.to_string());
+
+        let pesde_source = if self.prefer_pesde {
+            let pesde_source = PesdePackageSource::new(pesde_index);
+
+            match pesde_source.refresh(&project, &reqwest) {
+                Ok(()) => Some(pesde_source),
+                Err(e) => {
+                    log::warn!(
+                        "failed to refresh the pesde index, all dependencies will use wally: {e}"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        // the specifiers of the direct wally dependencies, used to seed the lockfile
+        let mut wally_specifiers = BTreeMap::new();
+
+        for (dependencies, ty) in [
+            (&wally_manifest.dependencies, DependencyType::Standard),
+            (
+                &wally_manifest.server_dependencies,
+                DependencyType::Standard,
+            ),
+            (&wally_manifest.dev_dependencies, DependencyType::Dev),
+        ] {
+            let dependency_key = match ty {
+                DependencyType::Dev => "dev_dependencies",
+                _ => "dependencies",
+            };
+
+            for (alias, specifier) in dependencies {
+                let field = &mut manifest[dependency_key]
+                    .or_insert(toml_edit::Item::Table(toml_edit::Table::new()))[alias];
+
+                let pesde_specifier = pesde_source
+                    .as_ref()
+                    .zip(pesde_name(&specifier.name))
+                    .map(|(source, name)| {
+                        (
+                            source,
+                            PesdeDependencySpecifier {
+                                name,
+                                version: specifier.version.clone(),
+                                index: None,
+                                target: None,
+                            },
+                        )
+                    })
+                    .filter(|(source, specifier)| {
+                        source
+                            .resolve(specifier, &project, &reqwest, target_kind)
+                            .is_ok_and(|(_, versions)| !versions.is_empty())
+                    })
+                    .map(|(_, specifier)| specifier);
+
+                match pesde_specifier {
+                    Some(pesde_specifier) => {
+                        field["name"] = toml_edit::value(pesde_specifier.name.to_string());
+                        field["version"] = toml_edit::value(specifier.version.to_string());
+
+                        println!(
+                            "using pesde {} for {alias}, which was {}",
+                            pesde_specifier.name, specifier.name
+                        );
+                    }
+                    None => {
+                        field["wally"] = toml_edit::value(specifier.name.to_string());
+                        field["version"] = toml_edit::value(specifier.version.to_string());
+
+                        wally_specifiers.insert(
+                            specifier.name.clone(),
+                            (
+                                alias.clone(),
+                                ty,
+                                WallyDependencySpecifier {
+                                    name: specifier.name.clone(),
+                                    version: specifier.version.clone(),
+                                    index: None,
+                                },
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        project.write_manifest(manifest.to_string())?;
+
+        println!("{}", "migrated wally manifest".green());
+
+        let wally_lockfile =
+            match std::fs::read_to_string(project.package_dir().join(WALLY_LOCKFILE_FILE_NAME)) {
+                Ok(lockfile) => lockfile,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+                Err(e) => return Err(e).context("failed to read wally lockfile"),
+            };
+        let wally_lockfile: WallyLockfile =
+            toml::from_str(&wally_lockfile).context("failed to parse wally lockfile")?;
+
+        let manifest = project
+            .deser_manifest()
+            .context("failed to read migrated manifest")?;
+        let wally_source = WallyPackageSource::new(
+            manifest
+                .wally_indices
+                .get(DEFAULT_INDEX_NAME)
+                .cloned()
+                .context("missing default wally index in migrated manifest")?,
+        );
+
+        let mut refreshed_sources = HashSet::new();
+        let source = pesde::source::PackageSources::Wally(wally_source.clone());
+        source
+            .refresh(&project, &reqwest)
+            .context("failed to refresh wally index")?;
+        refreshed_sources.insert(source);
+
+        // build a graph of the locked packages, which the resolver will then reuse where the manifest allows it
+        let mut seed = DependencyGraph::default();
+
+        for package in &wally_lockfile.packages {
+            if package.name == wally_manifest.package.name {
+                continue;
+            }
+
+            let version_id = VersionId::new(package.version.clone(), TargetKind::Roblox);
+            let specifier = WallyDependencySpecifier {
+                name: package.name.clone(),
+                version: VersionReq::parse(&format!("={}", package.version))?,
+                index: None,
+            };
+
+            let Some(pkg_ref) = wally_source
+                .resolve(&specifier, &project, &reqwest, target_kind)
+                .with_context(|| format!("failed to resolve locked package {}", package.name))?
+                .1
+                .remove(&version_id)
+            else {
+                log::warn!(
+                    "locked package {}@{} not found, it won't be seeded",
+                    package.name,
+                    package.version
+                );
+                continue;
+            };
+
+            let direct = wally_specifiers.get(&package.name);
+
+            seed.entry(PackageNames::Wally(package.name.clone()))
+                .or_default()
+                .insert(
+                    version_id,
+                    DependencyGraphNode {
+                        direct: direct.map(|(alias, _, specifier)| {
+                            (
+                                alias.clone(),
+                                DependencySpecifiers::Wally(specifier.clone()),
+                            )
+                        }),
+                        dependencies: package
+                            .dependencies
+                            .iter()
+                            .map(|(alias, dependency)| {
+                                let (name, version) = parse_locked_dependency(dependency)?;
+
+                                Ok((
+                                    PackageNames::Wally(name),
+                                    (VersionId::new(version, TargetKind::Roblox), alias.clone()),
+                                ))
+                            })
+                            .collect::<anyhow::Result<_>>()?,
+                        ty: direct.map_or(DependencyType::Standard, |(_, ty, _)| *ty),
+                        pkg_ref: PackageRefs::Wally(pkg_ref),
+                    },
+                );
+        }
+
+        let graph = project
+            .dependency_graph(Some(&seed), &mut refreshed_sources, &reqwest)
+            .context("failed to build dependency graph")?;
+
+        let downloaded_graph = download_graph(
+            &project,
+            &mut refreshed_sources,
+            &graph,
+            &multi,
+            &reqwest,
+            self.threads as usize,
+            false,
+            false,
+            false,
+            false,
+            "📥 downloading dependencies".to_string(),
+            "📥 downloaded dependencies".to_string(),
+        )?;
+
+        project
+            .write_lockfile(Lockfile {
+                format_version: CURRENT_FORMAT_VERSION,
+                name: manifest.name,
+                version: manifest.version,
+                target: manifest.target.kind(),
+                overrides: manifest.overrides,
+
+                graph: downloaded_graph,
+                members: Default::default(),
+
+                workspace: Default::default(),
+            })
+            .context("failed to write lockfile")?;
+        register_project(&project);
+
+        println!(
+            "{}",
+            format!("seeded lockfile from {WALLY_LOCKFILE_FILE_NAME}").green()
+        );
+
+        Ok(())
+    }
+}
@@ -3,10 +3,14 @@ use pesde::Project;
 
 mod add;
 mod auth;
+mod cas;
 mod config;
 mod execute;
 mod init;
 mod install;
+mod lock;
+#[cfg(feature = "wally-compat")]
+mod migrate;
 mod outdated;
 #[cfg(feature = "patches")]
 mod patch;
@@ -14,6 +18,8 @@ mod patch;
 mod patch_commit;
 mod publish;
 mod run;
+#[cfg(feature = "sbom")]
+mod sbom;
 #[cfg(feature = "version-management")]
 mod self_install;
 #[cfg(feature = "version-management")]
@@ -66,6 +72,23 @@ pub enum Subcommand {
     /// Checks for outdated dependencies
     Outdated(outdated::OutdatedCommand),
 
+    /// Lockfile-related commands
+    #[command(subcommand)]
+    Lock(lock::LockCommands),
+
+    /// CAS-related commands
+    #[command(subcommand)]
+    Cas(cas::CasCommands),
+
+    /// Migrates projects from other package managers
+    #[cfg(feature = "wally-compat")]
+    #[command(subcommand)]
+    Migrate(migrate::MigrateCommands),
+
+    /// Generates a software bill of materials from the lockfile
+    #[cfg(feature = "sbom")]
+    Sbom(sbom::SbomCommand),
+
     /// Executes a binary package without needing to be run in a project directory
     #[clap(name = "x", visible_alias = "execute", visible_alias = "exec")]
     Execute(execute::ExecuteCommand),
@@ -93,9 +116,15 @@ impl Subcommand {
             Subcommand::PatchCommit(patch_commit) => patch_commit.run(project),
             #[cfg(feature = "version-management")]
             Subcommand::SelfUpgrade(self_upgrade) => self_upgrade.run(reqwest),
-            Subcommand::Add(add) => add.run(project),
+            Subcommand::Add(add) => add.run(project, reqwest),
             Subcommand::Update(update) => update.run(project, multi, reqwest),
-            Subcommand::Outdated(outdated) => outdated.run(project),
+            Subcommand::Outdated(outdated) => outdated.run(project, reqwest),
+            Subcommand::Lock(lock) => lock.run(project),
+            Subcommand::Cas(cas) => cas.run(project, multi, reqwest),
+            #[cfg(feature = "wally-compat")]
+            Subcommand::Migrate(migrate) => migrate.run(project, multi, reqwest),
+            #[cfg(feature = "sbom")]
+            Subcommand::Sbom(sbom) => sbom.run(project),
             Subcommand::Execute(execute) => execute.run(project, reqwest),
         }
     }
@@ -6,6 +6,8 @@ use semver::VersionReq;
 
 use pesde::{
     source::{
+        git::specifier::GitVersion,
+        refs::PackageRefs,
         specifiers::DependencySpecifiers,
         traits::{PackageRef, PackageSource},
     },
@@ -20,7 +22,7 @@ pub struct OutdatedCommand {
 }
 
 impl OutdatedCommand {
-    pub fn run(self, project: Project) -> anyhow::Result<()> {
+    pub fn run(self, project: Project, reqwest: reqwest::blocking::Client) -> anyhow::Result<()> {
         let graph = project.deser_lockfile()?.graph;
 
         let manifest = project
@@ -35,9 +37,16 @@ impl OutdatedCommand {
                     continue;
                 };
 
+                // branches are the only git dependencies which can move
                 if matches!(
+                    &specifier,
+                    DependencySpecifiers::Git(spec) if !matches!(spec.version, GitVersion::Branch(_))
+                ) || matches!(
                     specifier,
-                    DependencySpecifiers::Git(_) | DependencySpecifiers::Workspace(_)
+                    DependencySpecifiers::Workspace(_)
+                        | DependencySpecifiers::Path(_)
+                        | DependencySpecifiers::Url(_)
+                        | DependencySpecifiers::Custom(_)
                 ) {
                     continue;
                 }
@@ -45,7 +54,7 @@ impl OutdatedCommand {
                 let source = node.node.pkg_ref.source();
 
                 if refreshed_sources.insert(source.clone()) {
-                    source.refresh(&project)?;
+                    source.refresh(&project, &reqwest)?;
                 }
 
                 if !self.strict {
@@ -59,17 +68,33 @@ impl OutdatedCommand {
                         }
                         DependencySpecifiers::Git(_) => {}
                         DependencySpecifiers::Workspace(_) => {}
+                        DependencySpecifiers::Path(_) => {}
+                        DependencySpecifiers::Url(_) => {}
+                        DependencySpecifiers::Custom(_) => {}
                     };
                 }
 
-                let version_id = source
-                    .resolve(&specifier, &project, manifest.target.kind())
+                let (version_id, pkg_ref) = source
+                    .resolve(&specifier, &project, &reqwest, manifest.target.kind())
                     .context("failed to resolve package versions")?
                     .1
                     .pop_last()
-                    .map(|(v_id, _)| v_id)
                     .context(format!("no versions of {specifier} found"))?;
 
+                if let (PackageRefs::Git(current), PackageRefs::Git(latest)) =
+                    (&node.node.pkg_ref, &pkg_ref)
+                {
+                    if current.commit != latest.commit {
+                        println!(
+                            "{name} ({alias}) {specifier} {} -> {}",
+                            current.commit.as_deref().unwrap_or("(unknown)"),
+                            latest.commit.as_deref().unwrap_or("(unknown)")
+                        );
+                    }
+
+                    continue;
+                }
+
                 if version_id != current_version_id {
                     println!("{name} ({alias}) {current_version_id} -> {version_id}");
                 }
@@ -5,6 +5,7 @@ use colored::Colorize;
 use pesde::{
     patches::setup_patches_repo,
     source::{
+        fs::LinkStrategy,
         refs::PackageRefs,
         traits::{PackageRef, PackageSource},
     },
@@ -37,6 +38,10 @@ impl PatchCommand {
             anyhow::bail!("cannot patch a workspace package")
         }
 
+        if matches!(node.node.pkg_ref, PackageRefs::Path(_)) {
+            anyhow::bail!("cannot patch a path package")
+        }
+
         let source = node.node.pkg_ref.source();
 
         let directory = project
@@ -48,9 +53,9 @@ impl PatchCommand {
         std::fs::create_dir_all(&directory)?;
 
         source
-            .download(&node.node.pkg_ref, &project, &reqwest)?
+            .download(&mut node.node.pkg_ref.clone(), &project, &reqwest)?
             .0
-            .write_to(&directory, project.cas_dir(), false)
+            .write_to(&directory, project.cas_dir(), LinkStrategy::Copy)
             .context("failed to write package contents")?;
 
         setup_patches_repo(&directory)?;
@@ -325,7 +325,7 @@ impl PublishCommand {
                 }
                 DependencySpecifiers::Workspace(spec) => {
                     let pkg_ref = WorkspacePackageSource
-                        .resolve(spec, project, target_kind)
+                        .resolve(spec, project, &reqwest, target_kind)
                         .context("failed to resolve workspace package")?
                         .1
                         .pop_last()
@@ -365,6 +365,21 @@ impl PublishCommand {
                         target: Some(spec.target.unwrap_or(manifest.target.kind())),
                     });
                 }
+                DependencySpecifiers::Path(spec) => {
+                    anyhow::bail!(
+                        "cannot publish a package with a path dependency ({spec}), publish the package it points to and depend on it by name instead"
+                    );
+                }
+                DependencySpecifiers::Url(spec) => {
+                    anyhow::bail!(
+                        "cannot publish a package with a url dependency ({spec}), publish the package it points to and depend on it by name instead"
+                    );
+                }
+                DependencySpecifiers::Custom(spec) => {
+                    anyhow::bail!(
+                        "cannot publish a package with a custom dependency ({spec}), as its source isn't available to other projects"
+                    );
+                }
             }
         }
 
@@ -463,7 +478,7 @@ impl PublishCommand {
             .context("missing default index")?;
         let source = PesdePackageSource::new(index_url.clone());
         source
-            .refresh(project)
+            .refresh(project, &reqwest)
             .context("failed to refresh source")?;
         let config = source
             .config(project)
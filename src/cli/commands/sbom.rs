@@ -0,0 +1,36 @@
+use anyhow::Context;
+use clap::Args;
+use pesde::{sbom::SbomFormat, Project};
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct SbomCommand {
+    /// The format of the bill of materials, either cyclonedx-json or spdx-json
+    #[arg(short, long, default_value = "cyclonedx-json")]
+    format: SbomFormat,
+
+    /// The file to write the bill of materials to, printed to stdout if not passed
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl SbomCommand {
+    pub fn run(self, project: Project) -> anyhow::Result<()> {
+        let lockfile = project
+            .deser_lockfile()
+            .context("failed to read lockfile")?;
+
+        let sbom = serde_json::to_string_pretty(&project.sbom(&lockfile, self.format))
+            .context("failed to serialize bill of materials")?;
+
+        match self.output {
+            Some(output) => {
+                std::fs::write(&output, sbom).context("failed to write bill of materials")?;
+                println!("wrote bill of materials to {}", output.display());
+            }
+            None => println!("{sbom}"),
+        }
+
+        Ok(())
+    }
+}
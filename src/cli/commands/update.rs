@@ -1,10 +1,16 @@
-use crate::cli::{download_graph, run_on_workspace_members};
+use crate::cli::{
+    download_graph, ensure_not_shared_lockfile_member, print_graph_diff, register_project,
+    run_on_workspace_members,
+};
 use anyhow::Context;
 use clap::Args;
 use colored::Colorize;
 use indicatif::MultiProgress;
-use pesde::{lockfile::Lockfile, Project};
-use std::collections::HashSet;
+use pesde::{
+    lockfile::{Lockfile, CURRENT_FORMAT_VERSION},
+    Project,
+};
+use std::collections::{BTreeMap, HashSet};
 
 #[derive(Debug, Args, Copy, Clone)]
 pub struct UpdateCommand {
@@ -26,6 +32,8 @@ impl UpdateCommand {
             .deser_manifest()
             .context("failed to read manifest")?;
 
+        ensure_not_shared_lockfile_member(&project, "update")?;
+
         println!(
             "\n{}\n",
             format!("[now updating {} {}]", manifest.name, manifest.target)
@@ -33,12 +41,85 @@ impl UpdateCommand {
                 .on_bright_black()
         );
 
-        let graph = project
-            .dependency_graph(None, &mut refreshed_sources)
-            .context("failed to build dependency graph")?;
+        let old_graph = match project.deser_lockfile() {
+            Ok(lockfile) => lockfile.graph,
+            Err(pesde::errors::LockfileReadError::Io(e))
+                if e.kind() == std::io::ErrorKind::NotFound =>
+            {
+                Default::default()
+            }
+            Err(e) => return Err(e).context("failed to read lockfile"),
+        };
 
-        project
-            .write_lockfile(Lockfile {
+        let lockfile = if manifest.shared_lockfile {
+            let graphs = project
+                .workspace_dependency_graph(&BTreeMap::new(), &mut refreshed_sources, &reqwest)
+                .context("failed to build dependency graph")?;
+
+            let mut downloaded_graphs = BTreeMap::new();
+            let mut workspace = BTreeMap::<_, BTreeMap<_, _>>::new();
+
+            for (path, graph) in graphs {
+                let member = if path.as_str().is_empty() {
+                    project.clone()
+                } else {
+                    let member = project.for_member(path.to_path(project.package_dir()));
+                    let member_manifest = member
+                        .deser_manifest()
+                        .context("failed to read workspace member manifest")?;
+
+                    workspace
+                        .entry(member_manifest.name)
+                        .or_default()
+                        .insert(member_manifest.target.kind(), path.clone());
+
+                    member
+                };
+
+                let downloaded_graph = download_graph(
+                    &member,
+                    &mut refreshed_sources,
+                    &graph,
+                    &multi,
+                    &reqwest,
+                    self.threads as usize,
+                    false,
+                    false,
+                    false,
+                    false,
+                    format!(
+                        "📥 downloading dependencies of {}",
+                        member.package_dir().display()
+                    ),
+                    format!(
+                        "📥 downloaded dependencies of {}",
+                        member.package_dir().display()
+                    ),
+                )?;
+                downloaded_graphs.insert(path, downloaded_graph);
+            }
+
+            let (graph, members) = Lockfile::combine_member_graphs(downloaded_graphs);
+
+            Lockfile {
+                format_version: CURRENT_FORMAT_VERSION,
+                name: manifest.name,
+                version: manifest.version,
+                target: manifest.target.kind(),
+                overrides: manifest.overrides,
+
+                graph,
+                members,
+
+                workspace,
+            }
+        } else {
+            let graph = project
+                .dependency_graph(None, &mut refreshed_sources, &reqwest)
+                .context("failed to build dependency graph")?;
+
+            Lockfile {
+                format_version: CURRENT_FORMAT_VERSION,
                 name: manifest.name,
                 version: manifest.version,
                 target: manifest.target.kind(),
@@ -53,15 +134,25 @@ impl UpdateCommand {
                     self.threads as usize,
                     false,
                     false,
+                    false,
+                    false,
                     "📥 downloading dependencies".to_string(),
                     "📥 downloaded dependencies".to_string(),
                 )?,
+                members: Default::default(),
 
                 workspace: run_on_workspace_members(&project, |project| {
                     self.run(project, multi.clone(), reqwest.clone())
                 })?,
-            })
+            }
+        };
+
+        print_graph_diff(&old_graph, &lockfile.graph);
+
+        project
+            .write_lockfile(lockfile)
             .context("failed to write lockfile")?;
+        register_project(&project);
 
         Ok(())
     }
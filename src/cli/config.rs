@@ -1,6 +1,50 @@
 use crate::cli::{auth::Tokens, home_dir};
 use anyhow::Context;
-use serde::{Deserialize, Serialize};
+use gix::bstr::BStr;
+use serde::{ser::SerializeMap, Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct Mirrors(pub BTreeMap<gix::Url, gix::Url>);
+
+impl Mirrors {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Serialize for Mirrors {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::ser::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (k, v) in &self.0 {
+            map.serialize_entry(&k.to_bstring().to_string(), &v.to_bstring().to_string())?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Mirrors {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::de::Deserializer<'de>,
+    {
+        Ok(Mirrors(
+            BTreeMap::<String, String>::deserialize(deserializer)?
+                .into_iter()
+                .map(|(k, v)| {
+                    Ok((
+                        gix::Url::from_bytes(BStr::new(&k))?,
+                        gix::Url::from_bytes(BStr::new(&v))?,
+                    ))
+                })
+                .collect::<Result<_, gix::url::parse::Error>>()
+                .map_err(serde::de::Error::custom)?,
+        ))
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliConfig {
@@ -17,6 +61,21 @@ pub struct CliConfig {
 
     pub tokens: Tokens,
 
+    #[serde(default, skip_serializing_if = "Mirrors::is_empty")]
+    pub mirrors: Mirrors,
+
+    #[serde(default)]
+    pub link_strategy: pesde::source::fs::LinkStrategy,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_retries: Option<u32>,
+    /// in seconds, including all retries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_timeout: Option<u64>,
+    /// in seconds, of a single attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub download_request_timeout: Option<u64>,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_checked_updates: Option<(chrono::DateTime<chrono::Utc>, semver::Version)>,
 }
@@ -33,6 +92,14 @@ impl Default for CliConfig {
 
             tokens: Tokens(Default::default()),
 
+            mirrors: Mirrors::default(),
+
+            link_strategy: Default::default(),
+
+            download_retries: None,
+            download_timeout: None,
+            download_request_timeout: None,
+
             last_checked_updates: None,
         }
     }
@@ -1,11 +1,12 @@
 use anyhow::Context;
+use colored::Colorize;
 use indicatif::MultiProgress;
 use pesde::{
-    lockfile::{DependencyGraph, DownloadedGraph, Lockfile},
-    manifest::target::TargetKind,
+    lockfile::{diff_graphs, DependencyGraph, DownloadedGraph, GraphChange, Lockfile},
+    manifest::{target::TargetKind, Manifest},
     names::{PackageName, PackageNames},
     source::{version_id::VersionId, workspace::specifier::VersionTypeOrReq, PackageSources},
-    Project,
+    Project, LOCKFILE_FILE_NAME, MANIFEST_FILE_NAME,
 };
 use relative_path::RelativePathBuf;
 use std::{
@@ -66,31 +67,72 @@ pub fn up_to_date_lockfile(project: &Project) -> anyhow::Result<Option<Lockfile>
         return Ok(None);
     }
 
-    let specs = lockfile
-        .graph
-        .iter()
-        .flat_map(|(_, versions)| versions)
-        .filter_map(|(_, node)| {
+    if !manifest.shared_lockfile {
+        let same_dependencies = dependencies_match(&manifest, &lockfile.graph)?;
+
+        log::debug!("dependencies are the same: {same_dependencies}");
+
+        if !same_dependencies {
+            return Ok(None);
+        }
+    } else {
+        let members = project
+            .workspace_members(project.package_dir())
+            .context("failed to get workspace members")?
+            .into_iter()
+            .map(|(path, manifest)| {
+                (
+                    RelativePathBuf::from_path(path.strip_prefix(project.package_dir()).unwrap())
+                        .unwrap(),
+                    manifest,
+                )
+            })
+            .chain(std::iter::once((RelativePathBuf::new(), manifest)))
+            .collect::<BTreeMap<_, _>>();
+
+        if members.len() != lockfile.members.len() {
+            log::debug!("workspace members are different");
+            return Ok(None);
+        }
+
+        for (path, member_manifest) in members {
+            let Some(graph) = lockfile.member_graph(&path) else {
+                log::debug!("workspace member {path} is not in the lockfile");
+                return Ok(None);
+            };
+
+            if !dependencies_match(&member_manifest, &graph)? {
+                log::debug!("dependencies of workspace member {path} are different");
+                return Ok(None);
+            }
+        }
+    }
+
+    Ok(Some(lockfile))
+}
+
+fn dependencies_match(manifest: &Manifest, graph: &DownloadedGraph) -> anyhow::Result<bool> {
+    let direct = graph
+        .values()
+        .flat_map(|versions| versions.values())
+        .filter_map(|node| {
             node.node
                 .direct
                 .as_ref()
                 .map(|(_, spec)| (spec, node.node.ty))
         })
-        .collect::<HashSet<_>>();
+        .collect::<Vec<_>>();
+    let specs = direct.iter().cloned().collect::<HashSet<_>>();
 
-    let same_dependencies = manifest
+    let all_dependencies = manifest
         .all_dependencies()
-        .context("failed to get all dependencies")?
-        .iter()
-        .all(|(_, (spec, ty))| specs.contains(&(spec, *ty)));
-
-    log::debug!("dependencies are the same: {same_dependencies}");
+        .context("failed to get all dependencies")?;
 
-    Ok(if same_dependencies {
-        Some(lockfile)
-    } else {
-        None
-    })
+    // a lockfile merged from conflicting sides may contain multiple direct entries for a dependency
+    Ok(direct.len() == all_dependencies.len()
+        && all_dependencies
+            .iter()
+            .all(|(_, (spec, ty))| specs.contains(&(spec, *ty))))
 }
 
 #[derive(Debug, Clone)]
@@ -150,6 +192,8 @@ enum AnyPackageIdentifier<V: FromStr = VersionId, N: FromStr = PackageNames> {
     PackageName(VersionedPackageName<V, N>),
     Url((gix::Url, String)),
     Workspace(VersionedPackageName<VersionTypeOrReq, PackageName>),
+    Path(RelativePathBuf),
+    Archive(url::Url),
 }
 
 impl<V: FromStr<Err = E>, E: Into<anyhow::Error>, N: FromStr<Err = F>, F: Into<anyhow::Error>>
@@ -168,6 +212,10 @@ impl<V: FromStr<Err = E>, E: Into<anyhow::Error>, N: FromStr<Err = F>, F: Into<a
             )))
         } else if let Some(rest) = s.strip_prefix("workspace:") {
             Ok(AnyPackageIdentifier::Workspace(rest.parse()?))
+        } else if let Some(rest) = s.strip_prefix("path:") {
+            Ok(AnyPackageIdentifier::Path(rest.into()))
+        } else if let Some(rest) = s.strip_prefix("url:") {
+            Ok(AnyPackageIdentifier::Archive(rest.parse()?))
         } else if s.contains(':') {
             let (url, rev) = s.split_once('#').context("missing revision")?;
 
@@ -195,6 +243,8 @@ pub fn download_graph(
     threads: usize,
     prod: bool,
     write: bool,
+    verify: bool,
+    skip_installed: bool,
     progress_msg: String,
     finish_msg: String,
 ) -> anyhow::Result<DownloadedGraph> {
@@ -209,7 +259,16 @@ pub fn download_graph(
     bar.enable_steady_tick(Duration::from_millis(100));
 
     let (rx, downloaded_graph) = project
-        .download_graph(graph, refreshed_sources, reqwest, threads, prod, write)
+        .download_graph(
+            graph,
+            refreshed_sources,
+            reqwest,
+            threads,
+            prod,
+            write,
+            verify,
+            skip_installed,
+        )
         .context("failed to download dependencies")?;
 
     while let Ok(result) = rx.recv() {
@@ -229,14 +288,73 @@ pub fn download_graph(
         .unwrap())
 }
 
-pub fn shift_project_dir(project: &Project, pkg_dir: PathBuf) -> Project {
-    Project::new(
-        pkg_dir,
-        Some(project.package_dir()),
-        project.data_dir(),
-        project.cas_dir(),
-        project.auth_config().clone(),
-    )
+/// Prints the changes between two graphs, returning whether there were any
+pub fn print_graph_diff(old: &DownloadedGraph, new: &DownloadedGraph) -> bool {
+    let changes = diff_graphs(old, new);
+
+    for (name, changes) in &changes {
+        for change in changes {
+            match change {
+                GraphChange::Added(version_id) => {
+                    println!("{} {name}@{version_id}", "+".green().bold())
+                }
+                GraphChange::Removed(version_id) => {
+                    println!("{} {name}@{version_id}", "-".red().bold())
+                }
+                GraphChange::Upgraded(from, to) => {
+                    println!("{} {name} {from} {} {to}", "↑".cyan().bold(), "→".dimmed())
+                }
+                GraphChange::Downgraded(from, to) => println!(
+                    "{} {name} {from} {} {to}",
+                    "↓".yellow().bold(),
+                    "→".dimmed()
+                ),
+                GraphChange::SourceChanged(version_id) => println!(
+                    "{} {name}@{version_id} {}",
+                    "~".magenta().bold(),
+                    "(source changed)".dimmed()
+                ),
+            }
+        }
+    }
+
+    !changes.is_empty()
+}
+
+/// Errors if the project is a member of a workspace which shares its lockfile, as the command must then be run from the workspace's root
+pub fn ensure_not_shared_lockfile_member(project: &Project, command: &str) -> anyhow::Result<()> {
+    let Some(workspace_dir) = project.workspace_dir() else {
+        return Ok(());
+    };
+
+    let manifest = std::fs::read_to_string(workspace_dir.join(MANIFEST_FILE_NAME))
+        .context("failed to read workspace manifest")?;
+    let manifest =
+        toml::from_str::<Manifest>(&manifest).context("failed to parse workspace manifest")?;
+
+    if manifest.shared_lockfile {
+        anyhow::bail!(
+            "this workspace shares its lockfile, run `{} {command}` in {} instead",
+            env!("CARGO_BIN_NAME"),
+            workspace_dir.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Records the project as using the data directory, so collecting the CAS' garbage keeps its dependencies
+pub fn register_project(project: &Project) {
+    if !project.package_dir().join(LOCKFILE_FILE_NAME).is_file() {
+        return;
+    }
+
+    if let Err(e) = project.register() {
+        log::warn!(
+            "failed to register project {}: {e}",
+            project.package_dir().display()
+        );
+    }
 }
 
 pub fn run_on_workspace_members(
@@ -262,11 +380,8 @@ pub fn run_on_workspace_members(
                 )
             })
             .map(|(name, target, path)| {
-                f(shift_project_dir(
-                    project,
-                    path.to_path(project.package_dir()),
-                ))
-                .map(|_| (name, target, path))
+                f(project.for_member(path.to_path(project.package_dir())))
+                    .map(|_| (name, target, path))
             })
             .collect::<Result<Vec<_>, _>>()
             .context("failed to install workspace member's dependencies")?
@@ -1,8 +1,14 @@
 use crate::{
+    cas::{file_hashes, invalidate_index_file, quarantine_cas_file, verify_cas_file},
     lockfile::{DependencyGraph, DownloadedDependencyGraphNode, DownloadedGraph},
     manifest::DependencyType,
+    names::PackageNames,
+    progress::{Phase, ProgressEvent, ProgressObserver},
     source::{
+        fs::{LinkStrategy, PackageFS},
+        refs::PackageRefs,
         traits::{PackageRef, PackageSource},
+        version_id::VersionId,
         PackageSources,
     },
     Project, PACKAGES_CONTAINER_NAME,
@@ -10,7 +16,12 @@ use crate::{
 use std::{
     collections::HashSet,
     fs::create_dir_all,
-    sync::{mpsc::Receiver, Arc, Mutex},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::Receiver,
+        Arc, Mutex,
+    },
 };
 
 type MultithreadedGraph = Arc<Mutex<DownloadedGraph>>;
@@ -20,8 +31,97 @@ type MultithreadDownloadJob = (
     MultithreadedGraph,
 );
 
+/// Checks the files of a package against their hashes before they are linked. Corrupted files are quarantined, and the
+/// package's cached file system removed, so the next download fetches the package again
+fn verify_fs(
+    project: &Project,
+    fs: &PackageFS,
+    pkg_ref: &PackageRefs,
+) -> Result<(), errors::DownloadGraphError> {
+    for hash in file_hashes(fs) {
+        if verify_cas_file(project.cas_dir(), hash)? {
+            continue;
+        }
+
+        quarantine_cas_file(project.cas_dir(), hash)?;
+        invalidate_index_file(project, pkg_ref)?;
+
+        return Err(errors::DownloadGraphError::Corrupted(hash.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Attributes the files downloaded by a job to the package it downloads
+#[derive(Debug)]
+struct PackageObserver {
+    inner: Arc<dyn ProgressObserver>,
+    name: PackageNames,
+    version_id: VersionId,
+}
+
+impl ProgressObserver for PackageObserver {
+    fn on_event(&self, event: &ProgressEvent) {
+        match event {
+            ProgressEvent::DownloadProgress {
+                package: None,
+                url,
+                downloaded,
+                total,
+            } => self.inner.on_event(&ProgressEvent::DownloadProgress {
+                package: Some((self.name.clone(), self.version_id.clone())),
+                url: url.clone(),
+                downloaded: *downloaded,
+                total: *total,
+            }),
+            event => self.inner.on_event(event),
+        }
+    }
+}
+
+/// Reports the end of the download phase when the last job of a graph is done, however it ended
+struct JobGuard {
+    observer: Arc<dyn ProgressObserver>,
+    remaining: Arc<AtomicUsize>,
+}
+
+impl Drop for JobGuard {
+    fn drop(&mut self) {
+        if self.remaining.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.observer
+                .on_event(&ProgressEvent::PhaseFinished(Phase::Download));
+        }
+    }
+}
+
+/// Writes a package to a temporary folder beside its container folder, then moves it into place
+fn write_container_folder(
+    fs: &PackageFS,
+    container_folder: &Path,
+    cas_dir: &Path,
+    link_strategy: LinkStrategy,
+) -> std::io::Result<()> {
+    let parent = container_folder
+        .parent()
+        .expect("container folders are nested in the packages folder");
+    create_dir_all(parent)?;
+
+    let tmp = tempfile::Builder::new().prefix(".tmp").tempdir_in(parent)?;
+    fs.write_to(tmp.path(), cas_dir, link_strategy)?;
+
+    match std::fs::rename(tmp.path(), container_folder) {
+        // another install wrote the package in the meantime
+        Err(_) if container_folder.is_dir() => Ok(()),
+        res => res,
+    }
+}
+
 impl Project {
-    /// Downloads a graph of dependencies
+    /// Downloads a graph of dependencies, verifying the files of the packages it links against their hashes if
+    /// `verify` is set. If `skip_installed` is set, packages whose container folder already exists are considered installed,
+    /// and aren't written again. Container folders are only moved into place once fully written, so an interrupted write
+    /// isn't mistaken for one
+    #[allow(clippy::too_many_arguments)]
     pub fn download_graph(
         &self,
         graph: &DependencyGraph,
@@ -30,24 +130,40 @@ impl Project {
         threads: usize,
         prod: bool,
         write: bool,
+        verify: bool,
+        skip_installed: bool,
     ) -> Result<MultithreadDownloadJob, errors::DownloadGraphError> {
         let manifest = self.deser_manifest()?;
+        let link_strategy = manifest.link_strategy.unwrap_or(self.link_strategy());
         let downloaded_graph: MultithreadedGraph = Arc::new(Mutex::new(Default::default()));
 
+        for node in graph.values().flat_map(|versions| versions.values()) {
+            let source = node.pkg_ref.source();
+
+            if refreshed_sources.insert(source.clone()) {
+                source.refresh(self, reqwest).map_err(Box::new)?;
+            }
+        }
+
         let threadpool = threadpool::ThreadPool::new(threads);
         let (tx, rx) = std::sync::mpsc::channel();
 
+        let remaining = Arc::new(AtomicUsize::new(
+            graph.values().map(|versions| versions.len()).sum(),
+        ));
+
+        self.report(ProgressEvent::PhaseStarted(Phase::Download));
+        if remaining.load(Ordering::SeqCst) == 0 {
+            self.report(ProgressEvent::PhaseFinished(Phase::Download));
+        }
+
         for (name, versions) in graph {
             for (version_id, node) in versions {
                 let source = node.pkg_ref.source();
 
-                if refreshed_sources.insert(source.clone()) {
-                    source.refresh(self).map_err(Box::new)?;
-                }
-
                 let container_folder = node.container_folder(
                     &self
-                        .package_dir()
+                        .packages_root()
                         .join(
                             manifest
                                 .target
@@ -59,24 +175,38 @@ impl Project {
                     version_id.version(),
                 );
 
-                create_dir_all(&container_folder)?;
+                let installed = skip_installed && container_folder.exists();
 
                 let tx = tx.clone();
 
                 let name = name.clone();
                 let version_id = version_id.clone();
-                let node = node.clone();
+                let mut node = node.clone();
 
-                let project = Arc::new(self.clone());
+                let project = Arc::new(self.clone().with_progress_observer(Arc::new(
+                    PackageObserver {
+                        inner: self.progress_observer().clone(),
+                        name: name.clone(),
+                        version_id: version_id.clone(),
+                    },
+                )));
                 let reqwest = reqwest.clone();
                 let downloaded_graph = downloaded_graph.clone();
+                let guard = JobGuard {
+                    observer: self.progress_observer().clone(),
+                    remaining: remaining.clone(),
+                };
 
                 threadpool.execute(move || {
-                    let project = project.clone();
+                    let _guard = guard;
 
                     log::debug!("downloading {name}@{version_id}");
+                    project.report(ProgressEvent::DownloadStarted {
+                        name: name.clone(),
+                        version_id: version_id.clone(),
+                    });
 
-                    let (fs, target) = match source.download(&node.pkg_ref, &project, &reqwest) {
+                    let (fs, target) = match source.download(&mut node.pkg_ref, &project, &reqwest) {
                         Ok(target) => target,
                         Err(e) => {
                             tx.send(Err(Box::new(e).into())).unwrap();
@@ -88,25 +218,39 @@ impl Project {
 
                     if write {
                         if !prod || node.ty != DependencyType::Dev {
-                            match fs.write_to(container_folder, project.cas_dir(), true) {
-                                Ok(_) => {}
-                                Err(e) => {
-                                    tx.send(Err(errors::DownloadGraphError::WriteFailed(e)))
-                                        .unwrap();
+                            if verify {
+                                if let Err(e) = verify_fs(&project, &fs, &node.pkg_ref) {
+                                    tx.send(Err(e)).unwrap();
                                     return;
                                 }
-                            };
+                            }
+
+                            if installed {
+                                log::debug!("{name}@{version_id} is already installed, not writing it again");
+                            } else if let Err(e) = write_container_folder(&fs, &container_folder, project.cas_dir(), link_strategy) {
+                                tx.send(Err(errors::DownloadGraphError::WriteFailed(e)))
+                                    .unwrap();
+                                return;
+                            }
                         } else {
                             log::debug!("skipping writing {name}@{version_id} to disk, dev dependency in prod mode");
                         }
                     }
 
-                    let mut downloaded_graph = downloaded_graph.lock().unwrap();
+                    project.report(ProgressEvent::DownloadFinished {
+                        name: name.clone(),
+                        version_id: version_id.clone(),
+                    });
+
                     downloaded_graph
+                        .lock()
+                        .unwrap()
                         .entry(name)
                         .or_default()
                         .insert(version_id, DownloadedDependencyGraphNode { node, target });
 
+                    // the graph is unwrapped once every job has reported back, so it must be released first
+                    drop(downloaded_graph);
                     tx.send(Ok(())).unwrap();
                 });
             }
@@ -116,6 +260,263 @@ impl Project {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lockfile::DependencyGraphNode,
+        manifest::target::{Target, TargetKind},
+        source::{
+            custom::{
+                pkg_ref::CustomPackageRef, specifier::CustomDependencySpecifier, BoxedError,
+                CustomSource,
+            },
+            ResolveResult,
+        },
+        AuthConfig, MANIFEST_FILE_NAME,
+    };
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    /// Records every event it is notified of
+    #[derive(Debug, Default)]
+    struct Recorder {
+        events: Mutex<Vec<ProgressEvent>>,
+    }
+
+    impl ProgressObserver for Recorder {
+        fn on_event(&self, event: &ProgressEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    impl Recorder {
+        /// The phases which were started (`true`) and finished (`false`), in order
+        fn phases(&self) -> Vec<(Phase, bool)> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|event| match event {
+                    ProgressEvent::PhaseStarted(phase) => Some((*phase, true)),
+                    ProgressEvent::PhaseFinished(phase) => Some((*phase, false)),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    /// Reports the progress of downloading a file without knowing the package it belongs to, failing packages
+    /// whose data asks it to
+    #[derive(Debug)]
+    struct TestSource;
+
+    impl CustomSource for TestSource {
+        fn resolve(
+            &self,
+            _specifier: &CustomDependencySpecifier,
+            _project: &Project,
+            _reqwest: &reqwest::blocking::Client,
+            _project_target: TargetKind,
+        ) -> Result<ResolveResult<CustomPackageRef>, BoxedError> {
+            unreachable!("graphs are downloaded as-is")
+        }
+
+        fn download(
+            &self,
+            pkg_ref: &mut CustomPackageRef,
+            project: &Project,
+            _reqwest: &reqwest::blocking::Client,
+        ) -> Result<(PackageFS, Target), BoxedError> {
+            project.report(ProgressEvent::DownloadProgress {
+                package: None,
+                url: "https://example.com/foo.tar.gz".to_string(),
+                downloaded: 3,
+                total: Some(3),
+            });
+
+            if pkg_ref.data.contains_key("fail") {
+                return Err("failed to download".into());
+            }
+
+            Ok((PackageFS::CAS(Default::default()), pkg_ref.target.clone()))
+        }
+    }
+
+    fn project(root: &Path) -> (Project, Arc<Recorder>) {
+        std::fs::create_dir_all(root.join("project")).unwrap();
+        std::fs::write(
+            root.join("project").join(MANIFEST_FILE_NAME),
+            "name = \"acme/app\"\nversion = \"0.1.0\"\n\n[target]\nenvironment = \"luau\"\n\n[scripts]\n",
+        )
+        .unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        let project = Project::new(
+            root.join("project"),
+            None::<PathBuf>,
+            root.join("data"),
+            root.join("cas"),
+            AuthConfig::default(),
+        )
+        .with_custom_sources([(
+            "test".to_string(),
+            Arc::new(TestSource) as Arc<dyn CustomSource>,
+        )])
+        .with_progress_observer(recorder.clone());
+
+        (project, recorder)
+    }
+
+    fn name() -> PackageNames {
+        PackageNames::Pesde("acme/foo".parse().unwrap())
+    }
+
+    fn version_id() -> VersionId {
+        "0.1.0 luau".parse().unwrap()
+    }
+
+    /// A graph of a single package from the test source, which fails to download if `fail` is set
+    fn graph(fail: bool) -> DependencyGraph {
+        let mut data = BTreeMap::new();
+        if fail {
+            data.insert("fail".to_string(), toml::Value::Boolean(true));
+        }
+
+        DependencyGraph::from([(
+            name(),
+            BTreeMap::from([(
+                version_id(),
+                DependencyGraphNode {
+                    direct: None,
+                    dependencies: Default::default(),
+                    ty: DependencyType::Standard,
+                    pkg_ref: PackageRefs::Custom(CustomPackageRef {
+                        custom: "test".to_string(),
+                        data,
+                        dependencies: Default::default(),
+                        target: Target::Luau {
+                            lib: None,
+                            bin: None,
+                        },
+                    }),
+                },
+            )]),
+        )])
+    }
+
+    /// Downloads the graph, waiting for every job to be done
+    fn download(
+        project: &Project,
+        graph: &DependencyGraph,
+    ) -> Result<DownloadedGraph, errors::DownloadGraphError> {
+        let (rx, downloaded_graph) = project.download_graph(
+            graph,
+            &mut HashSet::new(),
+            &reqwest::blocking::Client::new(),
+            2,
+            false,
+            true,
+            false,
+            false,
+        )?;
+
+        // the channel is only closed once every job is done
+        let results = rx.into_iter().collect::<Vec<_>>();
+        results.into_iter().collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Arc::into_inner(downloaded_graph)
+            .unwrap()
+            .into_inner()
+            .unwrap())
+    }
+
+    #[test]
+    fn pairs_phases_of_empty_graphs() {
+        let dir = tempfile::tempdir().unwrap();
+        let (project, recorder) = project(dir.path());
+
+        let downloaded_graph = download(&project, &Default::default()).unwrap();
+        project.link_dependencies(&downloaded_graph).unwrap();
+
+        assert_eq!(
+            recorder.phases(),
+            [
+                (Phase::Download, true),
+                (Phase::Download, false),
+                (Phase::Link, true),
+                (Phase::Link, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn attributes_downloads_to_their_package() {
+        let dir = tempfile::tempdir().unwrap();
+        let (project, recorder) = project(dir.path());
+
+        let downloaded_graph = download(&project, &graph(false)).unwrap();
+        project.link_dependencies(&downloaded_graph).unwrap();
+
+        assert_eq!(
+            recorder.phases(),
+            [
+                (Phase::Download, true),
+                (Phase::Download, false),
+                (Phase::Link, true),
+                (Phase::Link, false),
+            ]
+        );
+
+        let events = recorder.events.lock().unwrap();
+        assert!(events.iter().any(|event| matches!(
+            event,
+            ProgressEvent::DownloadProgress {
+                package: Some((name, version_id)),
+                downloaded: 3,
+                ..
+            } if *name == self::name() && *version_id == self::version_id()
+        )));
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, ProgressEvent::DownloadProgress { package: None, .. })));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            ProgressEvent::DownloadFinished { name, .. } if *name == self::name()
+        )));
+    }
+
+    #[test]
+    fn pairs_phases_of_failed_operations() {
+        let dir = tempfile::tempdir().unwrap();
+        let (project, recorder) = project(dir.path());
+
+        assert!(matches!(
+            download(&project, &graph(true)),
+            Err(errors::DownloadGraphError::DownloadFailed(..))
+        ));
+
+        // linking fails without a manifest
+        std::fs::remove_file(project.package_dir().join(MANIFEST_FILE_NAME)).unwrap();
+        assert!(project.link_dependencies(&Default::default()).is_err());
+
+        assert_eq!(
+            recorder.phases(),
+            [
+                (Phase::Download, true),
+                (Phase::Download, false),
+                (Phase::Link, true),
+                (Phase::Link, false),
+            ]
+        );
+        assert!(!recorder
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|event| matches!(event, ProgressEvent::DownloadFinished { .. })));
+    }
+}
+
 /// Errors that can occur when downloading a graph
 pub mod errors {
     use thiserror::Error;
@@ -143,5 +544,9 @@ pub mod errors {
         /// Error writing package contents
         #[error("failed to write package contents")]
         WriteFailed(std::io::Error),
+
+        /// A file of a package in the CAS doesn't match its hash
+        #[error("file {0} in the cas is corrupted. it has been quarantined, and will be downloaded again by the next install")]
+        Corrupted(String),
     }
 }
@@ -3,17 +3,28 @@
 //! pesde has its own registry, however it can also use Wally, and Git repositories as package sources.
 //! It has been designed with multiple targets in mind, namely Roblox, Lune, and Luau.
 
-use crate::{lockfile::Lockfile, manifest::Manifest};
-use gix::sec::identity::Account;
+use crate::{
+    lockfile::Lockfile,
+    manifest::Manifest,
+    progress::{NoopObserver, ProgressEvent, ProgressObserver},
+    retry::RetryConfig,
+    source::{custom::CustomSource, fs::LinkStrategy},
+};
+use gix::{bstr::BStr, sec::identity::Account};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
+/// Maintaining the CAS (content-addressable storage)
+pub mod cas;
 /// Downloading packages
 pub mod download;
 /// Linking packages
 pub mod linking;
+/// Locking files across processes
+pub mod lock;
 /// Lockfile
 pub mod lockfile;
 /// Manifest
@@ -23,8 +34,15 @@ pub mod names;
 /// Patching packages
 #[cfg(feature = "patches")]
 pub mod patches;
+/// Reporting progress
+pub mod progress;
 /// Resolving packages
 pub mod resolver;
+/// Retrying downloads
+pub mod retry;
+/// Generating software bills of materials
+#[cfg(feature = "sbom")]
+pub mod sbom;
 /// Running scripts
 pub mod scripts;
 /// Package sources
@@ -83,14 +101,81 @@ impl AuthConfig {
     }
 }
 
+/// Struct containing the mirrors to use instead of the canonical URLs of indices, repositories and downloads
+#[derive(Debug, Default, Clone)]
+pub struct MirrorConfig {
+    mirrors: BTreeMap<gix::Url, gix::Url>,
+}
+
+impl MirrorConfig {
+    /// Create a new `MirrorConfig`
+    pub fn new() -> Self {
+        MirrorConfig::default()
+    }
+
+    /// Set the mirrors, mapping canonical URLs to the URLs of their mirrors
+    pub fn with_mirrors<I: IntoIterator<Item = (gix::Url, gix::Url)>>(
+        mut self,
+        mirrors: I,
+    ) -> Self {
+        self.mirrors = mirrors.into_iter().collect();
+        self
+    }
+
+    /// Get the mirrors
+    pub fn mirrors(&self) -> &BTreeMap<gix::Url, gix::Url> {
+        &self.mirrors
+    }
+
+    /// Rewrites a URL to use the mirror of the longest canonical URL it starts with, if any
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        self.mirrors
+            .iter()
+            .filter_map(|(canonical, mirror)| {
+                let canonical = canonical.to_bstring().to_string();
+                let canonical = canonical.trim_end_matches('/');
+                let rest = url.strip_prefix(canonical)?;
+
+                if !(rest.is_empty() || rest.starts_with(['/', '?', '#'])) {
+                    return None;
+                }
+
+                let mirror = mirror.to_bstring().to_string();
+                Some((
+                    canonical.len(),
+                    format!("{}{rest}", mirror.trim_end_matches('/')),
+                ))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, url)| url)
+    }
+
+    /// Rewrites a Git URL to use its mirror, returning it unchanged if it has none
+    pub fn rewrite_url(&self, url: &gix::Url) -> gix::Url {
+        self.rewrite(&url.to_bstring().to_string())
+            .and_then(|rewritten| {
+                gix::Url::from_bytes(BStr::new(&rewritten))
+                    .inspect_err(|e| log::warn!("invalid mirror url {rewritten} for {url}: {e}"))
+                    .ok()
+            })
+            .unwrap_or_else(|| url.clone())
+    }
+}
+
 /// The main struct of the pesde library, representing a project
 #[derive(Debug, Clone)]
 pub struct Project {
     package_dir: PathBuf,
+    packages_root: Option<PathBuf>,
     workspace_dir: Option<PathBuf>,
     data_dir: PathBuf,
     auth_config: AuthConfig,
+    mirror_config: MirrorConfig,
     cas_dir: PathBuf,
+    custom_sources: BTreeMap<String, Arc<dyn CustomSource>>,
+    link_strategy: LinkStrategy,
+    retry_config: RetryConfig,
+    progress_observer: Arc<dyn ProgressObserver>,
 }
 
 impl Project {
@@ -104,10 +189,66 @@ impl Project {
     ) -> Self {
         Project {
             package_dir: package_dir.as_ref().to_path_buf(),
+            packages_root: None,
             workspace_dir: workspace_dir.map(|d| d.as_ref().to_path_buf()),
             data_dir: data_dir.as_ref().to_path_buf(),
             auth_config,
+            mirror_config: MirrorConfig::default(),
             cas_dir: cas_dir.as_ref().to_path_buf(),
+            custom_sources: BTreeMap::new(),
+            link_strategy: LinkStrategy::default(),
+            retry_config: RetryConfig::default(),
+            progress_observer: Arc::new(NoopObserver),
+        }
+    }
+
+    /// Register custom sources, which dependencies using the `custom` specifier with their name resolve with
+    pub fn with_custom_sources<I: IntoIterator<Item = (String, Arc<dyn CustomSource>)>>(
+        mut self,
+        custom_sources: I,
+    ) -> Self {
+        self.custom_sources.extend(custom_sources);
+        self
+    }
+
+    /// Set the mirrors to use instead of the canonical URLs of indices, repositories and downloads
+    pub fn with_mirror_config(mut self, mirror_config: MirrorConfig) -> Self {
+        self.mirror_config = mirror_config;
+        self
+    }
+
+    /// Set how failed downloads are retried
+    pub fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.retry_config = retry_config;
+        self
+    }
+
+    /// Set the observer the progress of operations on this project is reported to
+    pub fn with_progress_observer(mut self, progress_observer: Arc<dyn ProgressObserver>) -> Self {
+        self.progress_observer = progress_observer;
+        self
+    }
+
+    /// Place the packages folders in the given directory instead of the package directory, for example to
+    /// stage an install before moving it into place
+    pub fn with_packages_root<P: AsRef<Path>>(mut self, packages_root: P) -> Self {
+        self.packages_root = Some(packages_root.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set how files are placed from the CAS, unless the manifest specifies otherwise
+    pub fn with_link_strategy(mut self, link_strategy: LinkStrategy) -> Self {
+        self.link_strategy = link_strategy;
+        self
+    }
+
+    /// The project of a workspace member of this project, configured like this project
+    pub fn for_member<P: AsRef<Path>>(&self, path: P) -> Project {
+        Project {
+            package_dir: path.as_ref().to_path_buf(),
+            packages_root: None,
+            workspace_dir: Some(self.package_dir.clone()),
+            ..self.clone()
         }
     }
 
@@ -116,6 +257,11 @@ impl Project {
         &self.package_dir
     }
 
+    /// The directory the packages folders are placed in, which is the package directory unless set otherwise
+    pub fn packages_root(&self) -> &Path {
+        self.packages_root.as_deref().unwrap_or(&self.package_dir)
+    }
+
     /// The directory of the workspace this package belongs to, if any
     pub fn workspace_dir(&self) -> Option<&Path> {
         self.workspace_dir.as_deref()
@@ -131,11 +277,40 @@ impl Project {
         &self.auth_config
     }
 
+    /// The mirror configuration
+    pub fn mirror_config(&self) -> &MirrorConfig {
+        &self.mirror_config
+    }
+
+    /// The retry configuration
+    pub fn retry_config(&self) -> &RetryConfig {
+        &self.retry_config
+    }
+
+    /// The observer the progress of operations on this project is reported to
+    pub fn progress_observer(&self) -> &Arc<dyn ProgressObserver> {
+        &self.progress_observer
+    }
+
     /// The CAS (content-addressable storage) directory
     pub fn cas_dir(&self) -> &Path {
         &self.cas_dir
     }
 
+    /// The custom sources registered on this project
+    pub fn custom_sources(&self) -> &BTreeMap<String, Arc<dyn CustomSource>> {
+        &self.custom_sources
+    }
+
+    /// The default strategy for placing files from the CAS
+    pub fn link_strategy(&self) -> LinkStrategy {
+        self.link_strategy
+    }
+
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        self.progress_observer.on_event(&event);
+    }
+
     /// Read the manifest file
     pub fn read_manifest(&self) -> Result<String, errors::ManifestReadError> {
         let string = std::fs::read_to_string(self.package_dir.join(MANIFEST_FILE_NAME))?;
@@ -153,10 +328,10 @@ impl Project {
         std::fs::write(self.package_dir.join(MANIFEST_FILE_NAME), manifest.as_ref())
     }
 
-    /// Deserialize the lockfile
+    /// Deserialize the lockfile, merging both sides if it contains git merge conflicts
     pub fn deser_lockfile(&self) -> Result<Lockfile, errors::LockfileReadError> {
         let string = std::fs::read_to_string(self.package_dir.join(LOCKFILE_FILE_NAME))?;
-        Ok(toml::from_str(&string)?)
+        string.parse()
     }
 
     /// Write the lockfile
@@ -231,6 +406,22 @@ pub mod errors {
         /// An error occurred while deserializing the lockfile
         #[error("error deserializing lockfile")]
         Serde(#[from] toml::de::Error),
+
+        /// The lockfile's format version is not a valid version
+        #[error("lockfile has an invalid format version")]
+        InvalidFormatVersion,
+
+        /// The lockfile was written by a newer version of pesde
+        #[error("lockfile format version {0} is newer than the supported version {}, update pesde to use it", crate::lockfile::CURRENT_FORMAT_VERSION)]
+        TooNew(u64),
+
+        /// An error occurred while migrating the lockfile
+        #[error("error migrating lockfile")]
+        Migrate(#[from] crate::lockfile::errors::MigrateError),
+
+        /// One side of a merge conflict in the lockfile couldn't be read
+        #[error("error reading side of merge conflict in lockfile")]
+        Conflict(#[source] Box<LockfileReadError>),
     }
 
     /// Errors that can occur when writing the lockfile
@@ -2,12 +2,17 @@ use crate::{
     linking::generator::get_file_types,
     lockfile::DownloadedGraph,
     names::PackageNames,
+    progress::{Phase, ProgressEvent},
     scripts::{execute_script, ScriptName},
-    source::{fs::store_in_cas, traits::PackageRef, version_id::VersionId},
+    source::{
+        fs::{store_in_cas, LinkStrategy},
+        traits::PackageRef,
+        version_id::VersionId,
+    },
     Project, LINK_LIB_NO_FILE_FOUND, PACKAGES_CONTAINER_NAME,
 };
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ffi::OsStr,
     fs::create_dir_all,
     path::{Path, PathBuf},
@@ -22,17 +27,49 @@ fn create_and_canonicalize<P: AsRef<Path>>(path: P) -> std::io::Result<PathBuf>
     p.canonicalize()
 }
 
-fn write_cas(destination: PathBuf, cas_dir: &Path, contents: &str) -> std::io::Result<()> {
+fn write_cas(
+    destination: PathBuf,
+    cas_dir: &Path,
+    contents: &str,
+    link_strategy: LinkStrategy,
+) -> std::io::Result<PathBuf> {
+    // leave linking modules which are already up to date untouched, so file watchers don't pick them up
+    match std::fs::read_to_string(&destination) {
+        Ok(current) if current == contents => return Ok(destination),
+        Ok(_) => std::fs::remove_file(&destination)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
     let cas_path = store_in_cas(cas_dir, contents.as_bytes())?.1;
+    link_strategy.link(cas_path, &destination)?;
 
-    std::fs::hard_link(cas_path, destination)
+    Ok(destination)
 }
 
 impl Project {
-    /// Links the dependencies of the project
-    pub fn link_dependencies(&self, graph: &DownloadedGraph) -> Result<(), errors::LinkingError> {
+    /// Links the dependencies of the project, returning the paths of the linking modules
+    pub fn link_dependencies(
+        &self,
+        graph: &DownloadedGraph,
+    ) -> Result<BTreeSet<PathBuf>, errors::LinkingError> {
+        self.report(ProgressEvent::PhaseStarted(Phase::Link));
+
+        let linker_files = self.link_graph(graph);
+
+        self.report(ProgressEvent::PhaseFinished(Phase::Link));
+
+        linker_files
+    }
+
+    fn link_graph(
+        &self,
+        graph: &DownloadedGraph,
+    ) -> Result<BTreeSet<PathBuf>, errors::LinkingError> {
         let manifest = self.deser_manifest()?;
+        let link_strategy = manifest.link_strategy.unwrap_or(self.link_strategy());
 
+        let mut linker_files = BTreeSet::new();
         let mut package_types = BTreeMap::<&PackageNames, BTreeMap<&VersionId, Vec<String>>>::new();
 
         for (name, versions) in graph {
@@ -43,7 +80,7 @@ impl Project {
 
                 let container_folder = node.node.container_folder(
                     &self
-                        .package_dir()
+                        .packages_root()
                         .join(
                             manifest
                                 .target
@@ -123,7 +160,7 @@ impl Project {
             for (version_id, node) in versions {
                 let (node_container_folder, node_packages_folder) = {
                     let base_folder = create_and_canonicalize(
-                        self.package_dir().join(
+                        self.packages_root().join(
                             manifest
                                 .target
                                 .kind()
@@ -147,7 +184,7 @@ impl Project {
                                     .map(|types| (lib_file, types))
                             })
                         {
-                            write_cas(
+                            linker_files.insert(write_cas(
                                 base_folder.join(format!("{alias}.luau")),
                                 self.cas_dir(),
                                 &generator::generate_lib_linking_module(
@@ -163,11 +200,12 @@ impl Project {
                                     )?,
                                     types,
                                 ),
-                            )?;
+                                link_strategy,
+                            )?);
                         };
 
                         if let Some(bin_file) = node.target.bin_path() {
-                            write_cas(
+                            linker_files.insert(write_cas(
                                 base_folder.join(format!("{alias}.bin.luau")),
                                 self.cas_dir(),
                                 &generator::generate_bin_linking_module(
@@ -178,7 +216,8 @@ impl Project {
                                         &container_folder,
                                     ),
                                 ),
-                            )?;
+                                link_strategy,
+                            )?);
                         }
                     }
 
@@ -203,7 +242,7 @@ impl Project {
                     };
 
                     let base_folder = create_and_canonicalize(
-                        self.package_dir().join(
+                        self.packages_root().join(
                             node.node
                                 .pkg_ref
                                 .target_kind()
@@ -223,7 +262,7 @@ impl Project {
                             .join(node.node.base_folder(dependency_node.target.kind())),
                     )?;
 
-                    write_cas(
+                    linker_files.insert(write_cas(
                         linker_folder.join(format!("{dependency_alias}.luau")),
                         self.cas_dir(),
                         &generator::generate_lib_linking_module(
@@ -242,12 +281,18 @@ impl Project {
                                 .and_then(|v| v.get(dependency_version_id))
                                 .unwrap(),
                         ),
-                    )?;
+                        link_strategy,
+                    )?);
                 }
+
+                self.report(ProgressEvent::Linked {
+                    name: name.clone(),
+                    version_id: version_id.clone(),
+                });
             }
         }
 
-        Ok(())
+        Ok(linker_files)
     }
 }
 
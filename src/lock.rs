@@ -0,0 +1,192 @@
+use crate::{util::hash, Project};
+use same_file::Handle;
+use std::{
+    fs::{OpenOptions, TryLockError},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+/// The directory in the data directory the lock files of locked paths are kept in
+pub(crate) const LOCKS_DIR: &str = "locks";
+
+/// An exclusive advisory lock held on a file, released once dropped
+#[derive(Debug)]
+pub struct FileLock {
+    _file: Handle,
+}
+
+impl FileLock {
+    /// Locks the file at the given path, creating it if it doesn't exist. If another process holds the lock,
+    /// waits until it is released, logging who it is waiting for at the info level
+    pub fn acquire<P: AsRef<Path>, D: std::fmt::Display>(
+        path: P,
+        description: D,
+    ) -> std::io::Result<Self> {
+        let path = path.as_ref();
+
+        loop {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+
+            let mut file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(path)?;
+
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(TryLockError::WouldBlock) => {
+                    // the holder's pid can't be read on platforms with mandatory locks, such as Windows
+                    let mut pid = String::new();
+                    match file.read_to_string(&mut pid).map(|_| pid.trim()) {
+                        Ok(pid) if !pid.is_empty() => {
+                            log::info!("waiting for lock on {description} held by pid {pid}")
+                        }
+                        _ => {
+                            log::info!("waiting for lock on {description} held by another process")
+                        }
+                    }
+
+                    file.lock()?;
+                }
+                Err(TryLockError::Error(e)) => return Err(e),
+            }
+
+            let mut file = Handle::from_file(file)?;
+
+            // garbage collection may have removed the lock file before it was locked, in which case locking it
+            // excludes no one, so the path is locked anew
+            match Handle::from_path(path) {
+                Ok(current) if current == file => {}
+                Ok(_) => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            }
+
+            let file_mut = file.as_file_mut();
+            file_mut.set_len(0)?;
+            file_mut.rewind()?;
+            write!(file_mut, "{}", std::process::id())?;
+
+            return Ok(FileLock { _file: file });
+        }
+    }
+}
+
+/// Removes the lock file at the given path if no one holds its lock, returning whether it was removed
+pub(crate) fn remove_unlocked(path: &Path) -> std::io::Result<bool> {
+    let file = match OpenOptions::new().read(true).write(true).open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+
+    match file.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Ok(false),
+        Err(TryLockError::Error(e)) => return Err(e),
+    }
+
+    // it is removed while locked, so whoever opened it in the meantime locks the path anew once it is released
+    let file = Handle::from_file(file)?;
+    match Handle::from_path(path) {
+        Ok(current) if current == file => {}
+        Ok(_) => return Ok(false),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    }
+
+    std::fs::remove_file(path)?;
+
+    Ok(true)
+}
+
+impl Project {
+    /// Locks the given path for as long as the returned lock is held, across every process sharing this
+    /// project's data directory
+    pub fn lock_path<P: AsRef<Path>>(&self, path: P) -> std::io::Result<FileLock> {
+        let path = path.as_ref();
+
+        FileLock::acquire(
+            self.data_dir
+                .join(LOCKS_DIR)
+                .join(hash(canonical_path(path).as_os_str().as_encoded_bytes())),
+            path.display(),
+        )
+    }
+}
+
+/// Canonicalizes the longest existing ancestor of the path, so the same location results in the same path however
+/// it is spelled, and whether or not it exists yet. Falls back to the path itself if no ancestor can be canonicalized
+fn canonical_path(path: &Path) -> PathBuf {
+    let mut file_names = vec![];
+    let mut ancestor = path;
+
+    loop {
+        if let Ok(canonical) = ancestor.canonicalize() {
+            return file_names
+                .into_iter()
+                .rev()
+                .fold(canonical, |path, file_name| path.join(file_name));
+        }
+
+        match (ancestor.parent(), ancestor.file_name()) {
+            (Some(parent), Some(file_name)) => {
+                file_names.push(file_name);
+                ancestor = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalizes_paths_which_dont_exist_yet() {
+        let dir = tempfile::tempdir().unwrap();
+        let canonical = dir.path().canonicalize().unwrap();
+        std::fs::create_dir(dir.path().join("a")).unwrap();
+
+        assert_eq!(
+            canonical_path(&dir.path().join("a/../a/b/c")),
+            canonical.join("a/b/c")
+        );
+
+        std::fs::create_dir_all(dir.path().join("a/b/c")).unwrap();
+        assert_eq!(
+            canonical_path(&dir.path().join("a/./b/c")),
+            canonical.join("a/b/c")
+        );
+    }
+
+    #[test]
+    fn relocks_paths_whose_lock_file_was_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lock");
+
+        let lock = FileLock::acquire(&path, "test").unwrap();
+        assert!(!remove_unlocked(&path).unwrap());
+        drop(lock);
+
+        assert!(remove_unlocked(&path).unwrap());
+        assert!(!path.exists());
+
+        let _lock = FileLock::acquire(&path, "test").unwrap();
+        assert!(path.is_file());
+        assert!(!remove_unlocked(&path).unwrap());
+    }
+
+    #[test]
+    fn falls_back_to_the_path_itself() {
+        assert_eq!(
+            canonical_path(Path::new("does/not/exist")),
+            Path::new("does/not/exist")
+        );
+    }
+}
@@ -10,12 +10,13 @@ use crate::{
         version_id::VersionId,
     },
 };
-use relative_path::RelativePathBuf;
+use relative_path::{RelativePath, RelativePathBuf};
 use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{btree_map::Entry, BTreeMap},
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, VecDeque},
     path::{Path, PathBuf},
+    str::FromStr,
 };
 
 /// A graph of dependencies
@@ -114,9 +115,32 @@ pub struct DownloadedDependencyGraphNode {
 /// A graph of `DownloadedDependencyGraphNode`s
 pub type DownloadedGraph = Graph<DownloadedDependencyGraphNode>;
 
+/// The current version of the lockfile format
+pub const CURRENT_FORMAT_VERSION: u64 = 1;
+
+/// A migration of a lockfile document from one format version to the next
+type Migration = fn(&mut toml::Table) -> Result<(), errors::MigrateError>;
+
+/// The migrations for older lockfiles, where the migration at index `n` upgrades format version `n` to `n + 1`
+const MIGRATIONS: [Migration; CURRENT_FORMAT_VERSION as usize] = [
+    // version 0 lockfiles predate the `format_version` field, but are otherwise identical to version 1
+    |_| Ok(()),
+];
+
+/// The broader of two dependency types of the same package, so that it is installed wherever either requires it
+fn broader_type(a: DependencyType, b: DependencyType) -> DependencyType {
+    match (a, b) {
+        (DependencyType::Standard, _) | (_, DependencyType::Standard) => DependencyType::Standard,
+        (DependencyType::Peer, _) | (_, DependencyType::Peer) => DependencyType::Peer,
+        _ => DependencyType::Dev,
+    }
+}
+
 /// A lockfile
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Lockfile {
+    /// The format version of the lockfile
+    pub format_version: u64,
     /// The name of the package
     pub name: PackageName,
     /// The version of the package
@@ -131,7 +155,723 @@ pub struct Lockfile {
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub workspace: BTreeMap<PackageName, BTreeMap<TargetKind, RelativePathBuf>>,
 
-    /// The graph of dependencies
+    /// The graph of dependencies. If the workspace members share this lockfile, it contains the dependencies of all of them
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub graph: DownloadedGraph,
+
+    /// The direct dependencies of each workspace member sharing this lockfile, keyed by their path relative to the
+    /// package, including the package itself under an empty path
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub members: BTreeMap<RelativePathBuf, MemberDependencies>,
+}
+
+/// A direct dependency of a workspace member sharing its workspace's lockfile
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MemberDependency {
+    /// The name of the package
+    pub name: PackageNames,
+    /// The version of the package
+    pub version_id: VersionId,
+    /// The specifier the member depends on the package with
+    pub specifier: DependencySpecifiers,
+    /// The type of the dependency
+    pub ty: DependencyType,
+}
+
+/// The direct dependencies of a workspace member, keyed by their alias
+pub type MemberDependencies = BTreeMap<String, MemberDependency>;
+
+impl Lockfile {
+    /// Upgrades a lockfile document from the given format version to the current one
+    pub fn migrate(document: &mut toml::Table, from: u64) -> Result<(), errors::MigrateError> {
+        if from > CURRENT_FORMAT_VERSION {
+            return Err(errors::MigrateError::TooNew(from));
+        }
+
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+            log::debug!(
+                "migrating lockfile from format version {version} to {}",
+                version + 1
+            );
+            migration(document)?;
+        }
+
+        document.insert(
+            "format_version".to_string(),
+            toml::Value::Integer(CURRENT_FORMAT_VERSION as i64),
+        );
+
+        Ok(())
+    }
+
+    /// Combines the graphs of the members of a workspace sharing a lockfile, keyed by their path relative to the
+    /// workspace, into one graph and the direct dependencies of each member. Only the workspace's own direct
+    /// dependencies stay marked as such in the graph
+    pub fn combine_member_graphs(
+        graphs: BTreeMap<RelativePathBuf, DownloadedGraph>,
+    ) -> (
+        DownloadedGraph,
+        BTreeMap<RelativePathBuf, MemberDependencies>,
+    ) {
+        let mut combined = DownloadedGraph::new();
+        let mut members = BTreeMap::new();
+
+        for (path, graph) in graphs {
+            let is_root = path.as_str().is_empty();
+            let mut direct_dependencies = MemberDependencies::new();
+
+            for (name, versions) in graph {
+                for (version_id, mut node) in versions {
+                    if let Some((alias, specifier)) = &node.node.direct {
+                        direct_dependencies.insert(
+                            alias.clone(),
+                            MemberDependency {
+                                name: name.clone(),
+                                version_id: version_id.clone(),
+                                specifier: specifier.clone(),
+                                ty: node.node.ty,
+                            },
+                        );
+                    }
+
+                    if !is_root {
+                        node.node.direct = None;
+                    }
+
+                    match combined.entry(name.clone()).or_default().entry(version_id) {
+                        Entry::Vacant(entry) => {
+                            entry.insert(node);
+                        }
+                        Entry::Occupied(entry) => {
+                            let existing = entry.into_mut();
+                            existing.node.ty = broader_type(existing.node.ty, node.node.ty);
+                        }
+                    }
+                }
+            }
+
+            members.insert(path, direct_dependencies);
+        }
+
+        (combined, members)
+    }
+
+    /// The graph of a workspace member sharing this lockfile, keyed by its path relative to the package, made of the
+    /// packages its direct dependencies reach
+    pub fn member_graph(&self, path: &RelativePath) -> Option<DownloadedGraph> {
+        let direct_dependencies = self.members.get(path)?;
+
+        let mut graph = DownloadedGraph::new();
+        let mut queue = VecDeque::new();
+
+        for (alias, dependency) in direct_dependencies {
+            let Some(node) = self
+                .graph
+                .get(&dependency.name)
+                .and_then(|versions| versions.get(&dependency.version_id))
+            else {
+                log::debug!(
+                    "direct dependency {}@{} of workspace member {path} is not in the graph",
+                    dependency.name,
+                    dependency.version_id
+                );
+                continue;
+            };
+
+            let mut node = node.clone();
+            node.node.direct = Some((alias.clone(), dependency.specifier.clone()));
+            node.node.ty = dependency.ty;
+
+            queue.extend(node.node.dependencies.clone());
+            graph
+                .entry(dependency.name.clone())
+                .or_default()
+                .insert(dependency.version_id.clone(), node);
+        }
+
+        while let Some((name, (version_id, _))) = queue.pop_front() {
+            if graph
+                .get(&name)
+                .is_some_and(|versions| versions.contains_key(&version_id))
+            {
+                continue;
+            }
+
+            let Some(node) = self
+                .graph
+                .get(&name)
+                .and_then(|versions| versions.get(&version_id))
+            else {
+                continue;
+            };
+
+            let mut node = node.clone();
+            node.node.direct = None;
+
+            queue.extend(node.node.dependencies.clone());
+            graph.entry(name).or_default().insert(version_id, node);
+        }
+
+        Some(graph)
+    }
+
+    /// Merges another lockfile into this one, keeping this lockfile's entries where both have one
+    pub fn merge(mut self, other: Lockfile) -> Lockfile {
+        fn merge_graphs(graph: &mut DownloadedGraph, other: DownloadedGraph) {
+            for (name, versions) in other {
+                let entry = graph.entry(name).or_default();
+
+                for (version, node) in versions {
+                    entry.entry(version).or_insert(node);
+                }
+            }
+        }
+
+        merge_graphs(&mut self.graph, other.graph);
+
+        for (path, dependencies) in other.members {
+            let entry = self.members.entry(path).or_default();
+
+            for (alias, dependency) in dependencies {
+                entry.entry(alias).or_insert(dependency);
+            }
+        }
+
+        for (name, targets) in other.workspace {
+            let entry = self.workspace.entry(name).or_default();
+
+            for (target, path) in targets {
+                entry.entry(target).or_insert(path);
+            }
+        }
+
+        self
+    }
+}
+
+/// Splits a file containing git merge conflict markers into both sides of the conflict, or returns `None` if it has none
+pub fn split_conflicts(string: &str) -> Option<(String, String)> {
+    enum Section {
+        Both,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut section = Section::Both;
+    let mut has_conflicts = false;
+    let mut ours = String::new();
+    let mut theirs = String::new();
+
+    for line in string.split_inclusive('\n') {
+        section = match section {
+            Section::Both if line.starts_with("<<<<<<<") => {
+                has_conflicts = true;
+                Section::Ours
+            }
+            Section::Ours if line.starts_with("|||||||") => Section::Base,
+            Section::Ours | Section::Base if line.starts_with("=======") => Section::Theirs,
+            Section::Theirs if line.starts_with(">>>>>>>") => Section::Both,
+            section => {
+                match section {
+                    Section::Both => {
+                        ours.push_str(line);
+                        theirs.push_str(line);
+                    }
+                    Section::Ours => ours.push_str(line),
+                    Section::Base => {}
+                    Section::Theirs => theirs.push_str(line),
+                }
+
+                section
+            }
+        };
+    }
+
+    has_conflicts.then_some((ours, theirs))
+}
+
+impl FromStr for Lockfile {
+    type Err = crate::errors::LockfileReadError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((ours, theirs)) = split_conflicts(s) else {
+            return parse_versioned(s);
+        };
+
+        log::warn!("lockfile contains merge conflicts, merging both sides");
+
+        let ours = parse_versioned(&ours).map_err(|e| Self::Err::Conflict(Box::new(e)))?;
+        let theirs = parse_versioned(&theirs).map_err(|e| Self::Err::Conflict(Box::new(e)))?;
+
+        Ok(ours.merge(theirs))
+    }
+}
+
+fn parse_versioned(string: &str) -> Result<Lockfile, crate::errors::LockfileReadError> {
+    let mut document = toml::from_str::<toml::Table>(string)?;
+
+    let format_version = match document.get("format_version") {
+        None => 0,
+        Some(toml::Value::Integer(version)) if *version >= 0 => *version as u64,
+        Some(_) => return Err(crate::errors::LockfileReadError::InvalidFormatVersion),
+    };
+
+    if format_version > CURRENT_FORMAT_VERSION {
+        return Err(crate::errors::LockfileReadError::TooNew(format_version));
+    }
+
+    if format_version < CURRENT_FORMAT_VERSION {
+        Lockfile::migrate(&mut document, format_version)?;
+    }
+
+    Ok(toml::Value::Table(document).try_into()?)
+}
+
+/// A change to a package between two dependency graphs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphChange {
+    /// The package was added
+    Added(VersionId),
+    /// The package was removed
+    Removed(VersionId),
+    /// The package was upgraded from the first version to the second
+    Upgraded(VersionId, VersionId),
+    /// The package was downgraded from the first version to the second
+    Downgraded(VersionId, VersionId),
+    /// The package kept its version, but now comes from a different source
+    SourceChanged(VersionId),
+}
+
+/// Compares two dependency graphs, returning the changes made to each package going from `old` to `new`
+pub fn diff_graphs(
+    old: &DownloadedGraph,
+    new: &DownloadedGraph,
+) -> BTreeMap<PackageNames, Vec<GraphChange>> {
+    let empty = BTreeMap::new();
+    let mut changes = BTreeMap::new();
+
+    for name in old.keys().chain(new.keys()).collect::<BTreeSet<_>>() {
+        let old_versions = old.get(name).unwrap_or(&empty);
+        let new_versions = new.get(name).unwrap_or(&empty);
+        let mut package_changes = vec![];
+
+        let targets = old_versions
+            .keys()
+            .chain(new_versions.keys())
+            .map(|id| *id.target())
+            .collect::<BTreeSet<_>>();
+
+        for target in targets {
+            let mut removed = old_versions
+                .keys()
+                .filter(|id| *id.target() == target && !new_versions.contains_key(id));
+            let mut added = new_versions
+                .keys()
+                .filter(|id| *id.target() == target && !old_versions.contains_key(id));
+
+            loop {
+                package_changes.push(match (removed.next(), added.next()) {
+                    (Some(from), Some(to)) if to > from => {
+                        GraphChange::Upgraded(from.clone(), to.clone())
+                    }
+                    (Some(from), Some(to)) => GraphChange::Downgraded(from.clone(), to.clone()),
+                    (Some(from), None) => GraphChange::Removed(from.clone()),
+                    (None, Some(to)) => GraphChange::Added(to.clone()),
+                    (None, None) => break,
+                });
+            }
+        }
+
+        for (version_id, node) in old_versions {
+            let Some(new_node) = new_versions.get(version_id) else {
+                continue;
+            };
+
+            if node.node.pkg_ref.source() != new_node.node.pkg_ref.source() {
+                package_changes.push(GraphChange::SourceChanged(version_id.clone()));
+            }
+        }
+
+        if !package_changes.is_empty() {
+            changes.insert(name.clone(), package_changes);
+        }
+    }
+
+    changes
+}
+
+/// Errors that can occur when working with lockfiles
+pub mod errors {
+    use thiserror::Error;
+
+    /// Errors that can occur when migrating a lockfile
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum MigrateError {
+        /// The lockfile is newer than the current format version
+        #[error(
+            "lockfile format version {0} is newer than the supported version {}",
+            super::CURRENT_FORMAT_VERSION
+        )]
+        TooNew(u64),
+
+        /// The lockfile's contents couldn't be migrated
+        #[error("error migrating lockfile from format version {0}: {1}")]
+        Invalid(u64, String),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::LockfileReadError;
+
+    const HEADER: &str = r#"name = "acme/app"
+version = "0.1.0"
+target = "luau"
+"#;
+
+    #[test]
+    fn migrates_unversioned_lockfile() {
+        let lockfile = HEADER.parse::<Lockfile>().unwrap();
+
+        assert_eq!(lockfile.format_version, CURRENT_FORMAT_VERSION);
+        assert_eq!(lockfile.name.to_string(), "acme/app");
+    }
+
+    #[test]
+    fn reads_current_lockfile() {
+        let lockfile = format!("format_version = {CURRENT_FORMAT_VERSION}\n{HEADER}")
+            .parse::<Lockfile>()
+            .unwrap();
+
+        assert_eq!(lockfile.format_version, CURRENT_FORMAT_VERSION);
+    }
+
+    #[test]
+    fn rejects_newer_lockfile() {
+        let newer = CURRENT_FORMAT_VERSION + 1;
+        let err = format!("format_version = {newer}\n{HEADER}")
+            .parse::<Lockfile>()
+            .unwrap_err();
+
+        assert!(matches!(err, LockfileReadError::TooNew(version) if version == newer));
+    }
+
+    #[test]
+    fn rejects_invalid_format_version() {
+        let err = format!("format_version = \"one\"\n{HEADER}")
+            .parse::<Lockfile>()
+            .unwrap_err();
+
+        assert!(matches!(err, LockfileReadError::InvalidFormatVersion));
+    }
+
+    #[test]
+    fn tells_corrupt_lockfile_apart() {
+        let err = "name = ".parse::<Lockfile>().unwrap_err();
+
+        assert!(matches!(err, LockfileReadError::Serde(_)));
+    }
+
+    fn package(name: &str) -> String {
+        format!(
+            r#"[graph."acme/{name}"."0.1.0 luau"]
+ty = "standard"
+
+[graph."acme/{name}"."0.1.0 luau".target]
+environment = "luau"
+
+[graph."acme/{name}"."0.1.0 luau".pkg_ref]
+ref_ty = "pesde"
+name = "acme/{name}"
+version = "0.1.0"
+index_url = "file:///index"
+
+[graph."acme/{name}"."0.1.0 luau".pkg_ref.target]
+environment = "luau"
+"#
+        )
+    }
+
+    #[test]
+    fn splits_conflicts() {
+        let file = "a\n<<<<<<< ours\nb\n||||||| base\nc\n=======\nd\n>>>>>>> theirs\ne\n";
+
+        assert_eq!(
+            split_conflicts(file),
+            Some(("a\nb\ne\n".to_string(), "a\nd\ne\n".to_string()))
+        );
+        assert_eq!(split_conflicts("a\nb\n"), None);
+    }
+
+    #[test]
+    fn merges_conflicting_sides() {
+        let file = format!(
+            "{HEADER}\n{}<<<<<<< ours\n{}=======\n{}>>>>>>> theirs\n",
+            package("shared"),
+            package("ours"),
+            package("theirs")
+        );
+        let lockfile = file.parse::<Lockfile>().unwrap();
+
+        let names = lockfile
+            .graph
+            .keys()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["acme/ours", "acme/shared", "acme/theirs"]);
+    }
+
+    #[test]
+    fn reports_unreadable_conflict_side() {
+        let file = format!("{HEADER}<<<<<<< ours\nname = \n=======\n>>>>>>> theirs\n");
+
+        assert!(matches!(
+            file.parse::<Lockfile>(),
+            Err(LockfileReadError::Conflict(_))
+        ));
+    }
+
+    #[test]
+    fn merge_keeps_own_entries() {
+        let ours = format!("{HEADER}\n{}", package("foo"))
+            .parse::<Lockfile>()
+            .unwrap();
+        let mut theirs = ours.clone();
+        let node = theirs
+            .graph
+            .values_mut()
+            .next()
+            .unwrap()
+            .values_mut()
+            .next()
+            .unwrap();
+        node.node.ty = DependencyType::Peer;
+
+        let merged = ours.merge(theirs);
+        let node = merged
+            .graph
+            .values()
+            .next()
+            .unwrap()
+            .values()
+            .next()
+            .unwrap();
+        assert_eq!(node.node.ty, DependencyType::Standard);
+    }
+
+    #[test]
+    fn migrate_rejects_newer_document() {
+        let mut document = toml::Table::new();
+
+        assert!(matches!(
+            Lockfile::migrate(&mut document, CURRENT_FORMAT_VERSION + 1),
+            Err(errors::MigrateError::TooNew(_))
+        ));
+    }
+
+    fn node(
+        table: &str,
+        name: &str,
+        direct: Option<&str>,
+        ty: &str,
+        dependencies: &[&str],
+    ) -> String {
+        let direct = direct
+            .map(|alias| {
+                format!(
+                    "direct = [\"{alias}\", {{ name = \"acme/{name}\", version = \"^0.1.0\" }}]\n"
+                )
+            })
+            .unwrap_or_default();
+        let dependencies = dependencies
+            .iter()
+            .map(|dependency| {
+                format!("\"acme/{dependency}\" = [\"0.1.0 luau\", \"{dependency}\"]\n")
+            })
+            .collect::<String>();
+
+        format!(
+            r#"[{table}."acme/{name}"."0.1.0 luau"]
+{direct}ty = "{ty}"
+
+[{table}."acme/{name}"."0.1.0 luau".dependencies]
+{dependencies}
+[{table}."acme/{name}"."0.1.0 luau".target]
+environment = "luau"
+
+[{table}."acme/{name}"."0.1.0 luau".pkg_ref]
+ref_ty = "pesde"
+name = "acme/{name}"
+version = "0.1.0"
+index_url = "file:///index"
+
+[{table}."acme/{name}"."0.1.0 luau".pkg_ref.target]
+environment = "luau"
+"#
+        )
+    }
+
+    fn graph(nodes: &[String]) -> DownloadedGraph {
+        format!("{HEADER}\n{}", nodes.concat())
+            .parse::<Lockfile>()
+            .unwrap()
+            .graph
+    }
+
+    fn package_name(name: &str) -> PackageNames {
+        PackageNames::Pesde(format!("acme/{name}").parse().unwrap())
+    }
+
+    fn get<'a>(graph: &'a DownloadedGraph, name: &str) -> Option<&'a DependencyGraphNode> {
+        graph
+            .get(&package_name(name))
+            .and_then(|versions| versions.values().next())
+            .map(|node| &node.node)
+    }
+
+    fn shared_lockfile(graphs: BTreeMap<RelativePathBuf, DownloadedGraph>) -> Lockfile {
+        let mut lockfile = HEADER.parse::<Lockfile>().unwrap();
+        (lockfile.graph, lockfile.members) = Lockfile::combine_member_graphs(graphs);
+        lockfile
+    }
+
+    #[test]
+    fn combines_member_graphs() {
+        let lockfile = shared_lockfile(BTreeMap::from([
+            (
+                RelativePathBuf::new(),
+                graph(&[node("graph", "foo", Some("foo"), "dev", &[])]),
+            ),
+            (
+                RelativePathBuf::from("member"),
+                graph(&[
+                    node("graph", "bar", Some("bar"), "standard", &["foo"]),
+                    node("graph", "foo", None, "standard", &[]),
+                ]),
+            ),
+        ]));
+
+        // the workspace's own direct dependencies stay marked in the combined graph
+        assert!(get(&lockfile.graph, "foo").unwrap().direct.is_some());
+        assert!(get(&lockfile.graph, "bar").unwrap().direct.is_none());
+        assert_eq!(
+            get(&lockfile.graph, "foo").unwrap().ty,
+            DependencyType::Standard
+        );
+
+        let root = lockfile.member_graph(RelativePath::new("")).unwrap();
+        assert!(get(&root, "bar").is_none());
+        assert_eq!(get(&root, "foo").unwrap().ty, DependencyType::Dev);
+
+        let member = lockfile.member_graph(RelativePath::new("member")).unwrap();
+        assert_eq!(
+            get(&member, "bar").unwrap().direct.as_ref().unwrap().0,
+            "bar"
+        );
+        assert!(get(&member, "foo").unwrap().direct.is_none());
+        assert_eq!(get(&member, "foo").unwrap().ty, DependencyType::Standard);
+
+        assert!(lockfile.member_graph(RelativePath::new("other")).is_none());
+    }
+
+    fn release(name: &str, version: &str, index_url: &str) -> String {
+        format!(
+            r#"[graph."acme/{name}"."{version} luau"]
+ty = "standard"
+
+[graph."acme/{name}"."{version} luau".target]
+environment = "luau"
+
+[graph."acme/{name}"."{version} luau".pkg_ref]
+ref_ty = "pesde"
+name = "acme/{name}"
+version = "{version}"
+index_url = "{index_url}"
+
+[graph."acme/{name}"."{version} luau".pkg_ref.target]
+environment = "luau"
+"#
+        )
+    }
+
+    fn version_id(version: &str) -> VersionId {
+        format!("{version} luau").parse().unwrap()
+    }
+
+    fn changes(old: &[String], new: &[String], name: &str) -> Option<Vec<GraphChange>> {
+        diff_graphs(&graph(old), &graph(new)).remove(&package_name(name))
+    }
+
+    #[test]
+    fn diffs_added_and_removed_packages() {
+        let old = [release("foo", "1.0.0", "file:///index")];
+        let new = [release("bar", "1.0.0", "file:///index")];
+
+        assert_eq!(
+            changes(&old, &new, "foo"),
+            Some(vec![GraphChange::Removed(version_id("1.0.0"))])
+        );
+        assert_eq!(
+            changes(&old, &new, "bar"),
+            Some(vec![GraphChange::Added(version_id("1.0.0"))])
+        );
+    }
+
+    #[test]
+    fn diffs_version_changes() {
+        let old = [release("foo", "1.0.0", "file:///index")];
+        let new = [release("foo", "1.1.0", "file:///index")];
+
+        assert_eq!(
+            changes(&old, &new, "foo"),
+            Some(vec![GraphChange::Upgraded(
+                version_id("1.0.0"),
+                version_id("1.1.0")
+            )])
+        );
+        assert_eq!(
+            changes(&new, &old, "foo"),
+            Some(vec![GraphChange::Downgraded(
+                version_id("1.1.0"),
+                version_id("1.0.0")
+            )])
+        );
+    }
+
+    #[test]
+    fn diffs_source_changes() {
+        let old = [release("foo", "1.0.0", "file:///index")];
+        let new = [release("foo", "1.0.0", "file:///mirror")];
+
+        assert_eq!(
+            changes(&old, &new, "foo"),
+            Some(vec![GraphChange::SourceChanged(version_id("1.0.0"))])
+        );
+        assert_eq!(changes(&old, &old, "foo"), None);
+    }
+
+    #[test]
+    fn diffs_packages_with_several_versions() {
+        let old = [
+            release("foo", "1.0.0", "file:///index"),
+            release("foo", "2.0.0", "file:///index"),
+        ];
+        let new = [
+            release("foo", "1.0.0", "file:///index"),
+            release("foo", "2.1.0", "file:///index"),
+            release("foo", "3.0.0", "file:///index"),
+        ];
+
+        // the version both graphs share is left out, and the others are paired up in order
+        assert_eq!(
+            changes(&old, &new, "foo"),
+            Some(vec![
+                GraphChange::Upgraded(version_id("2.0.0"), version_id("2.1.0")),
+                GraphChange::Added(version_id("3.0.0")),
+            ])
+        );
+    }
 }
@@ -3,7 +3,7 @@ use clap::Parser;
 use colored::Colorize;
 use indicatif::MultiProgress;
 use indicatif_log_bridge::LogWrapper;
-use pesde::{AuthConfig, Project, MANIFEST_FILE_NAME};
+use pesde::{retry::RetryConfig, AuthConfig, MirrorConfig, Project, MANIFEST_FILE_NAME};
 use std::{
     collections::HashSet,
     fs::create_dir_all,
@@ -14,7 +14,12 @@ use std::{
 use crate::cli::version::{
     check_for_updates, current_version, get_or_download_version, max_installed_version,
 };
-use crate::cli::{auth::get_tokens, home_dir, repos::update_repo_dependencies, HOME_DIR};
+#[cfg(windows)]
+use crate::cli::HOME_DIR;
+use crate::cli::{
+    auth::get_tokens, config::read_config, home_dir, register_project,
+    repos::update_repo_dependencies,
+};
 
 mod cli;
 pub mod util;
@@ -34,39 +39,6 @@ struct Cli {
     subcommand: cli::commands::Subcommand,
 }
 
-#[cfg(windows)]
-fn get_root(path: &std::path::Path) -> PathBuf {
-    match path.components().next().unwrap() {
-        std::path::Component::Prefix(prefix) => {
-            let mut string = prefix.as_os_str().to_string_lossy().to_string();
-            if string.ends_with(':') {
-                string.push(std::path::MAIN_SEPARATOR);
-            }
-
-            std::path::PathBuf::from(&string)
-        }
-        _ => unreachable!(),
-    }
-}
-
-#[cfg(unix)]
-fn get_root(path: &std::path::Path) -> PathBuf {
-    use std::os::unix::fs::MetadataExt;
-
-    let path = std::fs::canonicalize(path).unwrap();
-    let mut current = path.as_path();
-
-    while let Some(parent) = current.parent() {
-        if std::fs::metadata(parent).unwrap().dev() != std::fs::metadata(current).unwrap().dev() {
-            break;
-        }
-
-        current = parent;
-    }
-
-    current.to_path_buf()
-}
-
 fn run() -> anyhow::Result<()> {
     let cwd = std::env::current_dir().expect("failed to get current working directory");
 
@@ -172,6 +144,7 @@ fn run() -> anyhow::Result<()> {
     };
 
     let multi = {
+        // info is shown by default, as that is what waiting for a lock held by another process is logged at
         let logger = pretty_env_logger::formatted_builder()
             .parse_env(pretty_env_logger::env_logger::Env::default().default_filter_or("info"))
             .build();
@@ -185,16 +158,21 @@ fn run() -> anyhow::Result<()> {
     let data_dir = home_dir()?.join("data");
     create_dir_all(&data_dir).expect("failed to create data directory");
 
-    let home_cas_dir = data_dir.join("cas");
-    create_dir_all(&home_cas_dir).expect("failed to create cas directory");
-    let project_root = get_root(&project_root_dir);
-    let cas_dir = if get_root(&home_cas_dir) == project_root {
-        log::debug!("using home cas dir");
-        home_cas_dir
-    } else {
-        log::debug!("using cas dir in {}", project_root.display());
-        project_root.join(HOME_DIR).join("cas")
-    };
+    let cas_dir = data_dir.join("cas");
+    create_dir_all(&cas_dir).expect("failed to create cas directory");
+
+    let config = read_config()?;
+
+    let mut retry_config = RetryConfig::new();
+    if let Some(retries) = config.download_retries {
+        retry_config = retry_config.with_retries(retries);
+    }
+    if let Some(timeout) = config.download_timeout {
+        retry_config = retry_config.with_total_timeout(std::time::Duration::from_secs(timeout));
+    }
+    if let Some(timeout) = config.download_request_timeout {
+        retry_config = retry_config.with_request_timeout(std::time::Duration::from_secs(timeout));
+    }
 
     let project = Project::new(
         project_root_dir,
@@ -202,7 +180,21 @@ fn run() -> anyhow::Result<()> {
         data_dir,
         cas_dir,
         AuthConfig::new().with_tokens(get_tokens()?.0),
-    );
+    )
+    .with_link_strategy(config.link_strategy)
+    .with_retry_config(retry_config);
+
+    // mirrors the user configured take precedence over the ones the project suggests
+    let mirrors = project
+        .deser_manifest()
+        .map(|manifest| manifest.mirrors)
+        .unwrap_or_default()
+        .into_iter()
+        .chain(config.mirrors.0);
+    let project = project.with_mirror_config(MirrorConfig::new().with_mirrors(mirrors));
+
+    // projects installed before the data directory tracked them are registered once pesde is used in them
+    register_project(&project);
 
     let reqwest = {
         let mut headers = reqwest::header::HeaderMap::new();
@@ -86,9 +86,25 @@ pub struct Manifest {
     /// A list of globs pointing to workspace members' directories
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub workspace_members: Vec<String>,
+    /// Whether workspace members share this package's lockfile instead of each having their own
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shared_lockfile: bool,
     /// The Roblox place of this project
     #[serde(default, skip_serializing_if="BTreeMap::is_empty")]
     pub place: BTreeMap<target::RobloxPlaceKind, String>,
+    /// How files of dependencies are placed from the CAS, overriding the user's configuration
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_strategy: Option<crate::source::fs::LinkStrategy>,
+    /// Mirrors to use instead of the canonical URLs of indices, repositories and downloads. Mirrors the user configured
+    /// for the same URLs take precedence
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        serialize_with = "crate::util::serialize_gix_url_pairs",
+        deserialize_with = "crate::util::deserialize_gix_url_pairs"
+    )]
+    #[schemars(with = "BTreeMap<String, url::Url>")]
+    pub mirrors: BTreeMap<gix::Url, gix::Url>,
 
     /// The standard dependencies of the package
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
@@ -1,6 +1,8 @@
 use crate::{
-    lockfile::DownloadedGraph, source::traits::PackageRef, Project, MANIFEST_FILE_NAME,
-    PACKAGES_CONTAINER_NAME,
+    lockfile::DownloadedGraph,
+    progress::{Phase, ProgressEvent},
+    source::traits::PackageRef,
+    Project, MANIFEST_FILE_NAME, PACKAGES_CONTAINER_NAME,
 };
 use git2::{ApplyLocation, ApplyOptions, Diff, DiffFormat, DiffLineType, Repository, Signature};
 use relative_path::RelativePathBuf;
@@ -72,6 +74,16 @@ pub fn create_patch<P: AsRef<Path>>(dir: P) -> Result<Vec<u8>, git2::Error> {
 impl Project {
     /// Apply patches to the project's dependencies
     pub fn apply_patches(&self, graph: &DownloadedGraph) -> Result<(), errors::ApplyPatchesError> {
+        self.report(ProgressEvent::PhaseStarted(Phase::Patch));
+
+        let result = self.apply_patches_to(graph);
+
+        self.report(ProgressEvent::PhaseFinished(Phase::Patch));
+
+        result
+    }
+
+    fn apply_patches_to(&self, graph: &DownloadedGraph) -> Result<(), errors::ApplyPatchesError> {
         let manifest = self.deser_manifest()?;
 
         for (name, versions) in manifest.patches {
@@ -93,7 +105,7 @@ impl Project {
 
                 let container_folder = node.node.container_folder(
                     &self
-                        .package_dir()
+                        .packages_root()
                         .join(
                             manifest
                                 .target
@@ -147,6 +159,11 @@ impl Project {
                 std::fs::remove_dir_all(container_folder.join(".git")).map_err(|e| {
                     errors::ApplyPatchesError::GitDirectoryRemovalError(container_folder, e)
                 })?;
+
+                self.report(ProgressEvent::PatchApplied {
+                    name: name.clone(),
+                    version_id,
+                });
             }
         }
 
@@ -0,0 +1,95 @@
+use crate::{
+    names::PackageNames,
+    source::{version_id::VersionId, PackageSources},
+};
+use std::fmt::Debug;
+
+/// A phase of installing a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Phase {
+    /// Resolving the dependency graph
+    Resolve,
+    /// Downloading the packages of the graph
+    Download,
+    /// Linking the packages
+    Link,
+    /// Applying patches to the packages
+    Patch,
+}
+
+/// An event reported to a [`ProgressObserver`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ProgressEvent {
+    /// A phase started
+    PhaseStarted(Phase),
+    /// A phase finished, successfully or not
+    PhaseFinished(Phase),
+    /// A source started refreshing
+    RefreshStarted(PackageSources),
+    /// A source finished refreshing, successfully or not
+    RefreshFinished(PackageSources),
+    /// A package was added to the dependency graph
+    Resolved {
+        /// The name of the package
+        name: PackageNames,
+        /// The version of the package
+        version_id: VersionId,
+    },
+    /// A package started downloading
+    DownloadStarted {
+        /// The name of the package
+        name: PackageNames,
+        /// The version of the package
+        version_id: VersionId,
+    },
+    /// Bytes of a file were received
+    DownloadProgress {
+        /// The package the file belongs to, if it is downloaded as part of a graph
+        package: Option<(PackageNames, VersionId)>,
+        /// The URL of the file
+        url: String,
+        /// How many bytes have been received so far in this attempt
+        downloaded: u64,
+        /// The size of the file, if the server sent it
+        total: Option<u64>,
+    },
+    /// A package finished downloading and was written to disk
+    DownloadFinished {
+        /// The name of the package
+        name: PackageNames,
+        /// The version of the package
+        version_id: VersionId,
+    },
+    /// The dependencies of a package were linked
+    Linked {
+        /// The name of the package
+        name: PackageNames,
+        /// The version of the package
+        version_id: VersionId,
+    },
+    /// A patch was applied to a package
+    PatchApplied {
+        /// The name of the package
+        name: PackageNames,
+        /// The version of the package
+        version_id: VersionId,
+    },
+}
+
+/// Observes the progress of the operations done on a [`Project`](crate::Project), registered with
+/// [`Project::with_progress_observer`](crate::Project::with_progress_observer). Events may be reported from multiple
+/// threads at once, and should be handled quickly, as the operation waits for the observer
+pub trait ProgressObserver: Debug + Send + Sync {
+    /// Called for every event
+    fn on_event(&self, event: &ProgressEvent);
+}
+
+/// The observer of projects without one, which ignores all events
+#[derive(Debug)]
+pub(crate) struct NoopObserver;
+
+impl ProgressObserver for NoopObserver {
+    fn on_event(&self, _event: &ProgressEvent) {}
+}
@@ -2,6 +2,7 @@ use crate::{
     lockfile::{insert_node, DependencyGraph, DependencyGraphNode},
     manifest::DependencyType,
     names::PackageNames,
+    progress::{Phase, ProgressEvent},
     source::{
         pesde::PesdePackageSource,
         specifiers::DependencySpecifiers,
@@ -11,7 +12,46 @@ use crate::{
     },
     Project, DEFAULT_INDEX_NAME,
 };
-use std::collections::{HashMap, HashSet, VecDeque};
+use relative_path::RelativePathBuf;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Whether a package, or any package it depends on, has a version in the old graph other than the ones already picked in
+/// the shared graph of a workspace
+fn conflicts_with_shared(
+    previous_graph: &DependencyGraph,
+    name: &PackageNames,
+    version_id: &VersionId,
+    shared: &DependencyGraph,
+) -> bool {
+    let mut queue = VecDeque::from([(name, version_id)]);
+    let mut visited = HashSet::new();
+
+    while let Some((name, version_id)) = queue.pop_front() {
+        if !visited.insert((name, version_id)) {
+            continue;
+        }
+
+        if shared.get(name).is_some_and(|versions| {
+            !versions.contains_key(version_id)
+                && versions.keys().any(|id| id.target() == version_id.target())
+        }) {
+            return true;
+        }
+
+        if let Some(node) = previous_graph
+            .get(name)
+            .and_then(|versions| versions.get(version_id))
+        {
+            queue.extend(
+                node.dependencies
+                    .iter()
+                    .map(|(name, (version_id, _))| (name, version_id)),
+            );
+        }
+    }
+
+    false
+}
 
 impl Project {
     /// Create a dependency graph from the project's manifest
@@ -19,6 +59,114 @@ impl Project {
         &self,
         previous_graph: Option<&DependencyGraph>,
         refreshed_sources: &mut HashSet<PackageSources>,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<DependencyGraph, Box<errors::DependencyGraphError>> {
+        self.report(ProgressEvent::PhaseStarted(Phase::Resolve));
+
+        let graph = self.dependency_graph_with(
+            previous_graph,
+            refreshed_sources,
+            reqwest,
+            &DependencyGraph::default(),
+        );
+
+        self.report(ProgressEvent::PhaseFinished(Phase::Resolve));
+
+        graph
+    }
+
+    /// Create dependency graphs for the project and all of its workspace members, keyed by their path relative to the project.
+    /// The project itself is keyed by an empty path. Members prefer versions already picked by the project or other members,
+    /// so that the whole workspace agrees on the versions of its dependencies wherever their requirements allow it
+    pub fn workspace_dependency_graph(
+        &self,
+        previous_graphs: &BTreeMap<RelativePathBuf, DependencyGraph>,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<
+        BTreeMap<RelativePathBuf, DependencyGraph>,
+        Box<errors::WorkspaceDependencyGraphError>,
+    > {
+        self.report(ProgressEvent::PhaseStarted(Phase::Resolve));
+
+        let graphs = self.resolve_workspace(previous_graphs, refreshed_sources, reqwest);
+
+        self.report(ProgressEvent::PhaseFinished(Phase::Resolve));
+
+        graphs
+    }
+
+    fn resolve_workspace(
+        &self,
+        previous_graphs: &BTreeMap<RelativePathBuf, DependencyGraph>,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<
+        BTreeMap<RelativePathBuf, DependencyGraph>,
+        Box<errors::WorkspaceDependencyGraphError>,
+    > {
+        let mut members = self
+            .workspace_members(self.package_dir())
+            .map_err(|e| Box::new(e.into()))?
+            .into_keys()
+            .map(|path| {
+                let relative =
+                    RelativePathBuf::from_path(path.strip_prefix(self.package_dir()).unwrap())
+                        .unwrap();
+                let project = self.for_member(path);
+
+                (relative, project)
+            })
+            .collect::<BTreeMap<_, _>>();
+        members.insert(RelativePathBuf::new(), self.clone());
+
+        let mut shared = DependencyGraph::default();
+        let mut graphs = BTreeMap::new();
+
+        // resolve the project itself first, so that its choices take precedence
+        let root = members.remove_entry(&RelativePathBuf::new()).unwrap();
+        for (path, project) in std::iter::once(root).chain(members) {
+            log::debug!("resolving workspace member at {path:?}");
+
+            let graph = project
+                .dependency_graph_with(
+                    previous_graphs.get(&path),
+                    refreshed_sources,
+                    reqwest,
+                    &shared,
+                )
+                .map_err(|e| {
+                    Box::new(errors::WorkspaceDependencyGraphError::Member(
+                        path.clone(),
+                        e,
+                    ))
+                })?;
+
+            for (name, versions) in &graph {
+                for (version_id, node) in versions {
+                    shared
+                        .entry(name.clone())
+                        .or_default()
+                        .entry(version_id.clone())
+                        .or_insert_with(|| DependencyGraphNode {
+                            direct: None,
+                            ..node.clone()
+                        });
+                }
+            }
+
+            graphs.insert(path, graph);
+        }
+
+        Ok(graphs)
+    }
+
+    fn dependency_graph_with(
+        &self,
+        previous_graph: Option<&DependencyGraph>,
+        refreshed_sources: &mut HashSet<PackageSources>,
+        reqwest: &reqwest::blocking::Client,
+        shared: &DependencyGraph,
     ) -> Result<DependencyGraph, Box<errors::DependencyGraphError>> {
         let manifest = self.deser_manifest().map_err(|e| Box::new(e.into()))?;
 
@@ -39,8 +187,18 @@ impl Project {
                         continue;
                     };
 
-                    if matches!(specifier, DependencySpecifiers::Workspace(_)) {
-                        // workspace dependencies must always be resolved brand new
+                    if matches!(
+                        specifier,
+                        DependencySpecifiers::Workspace(_) | DependencySpecifiers::Path(_)
+                    ) {
+                        // workspace and path dependencies must always be resolved brand new
+                        continue;
+                    }
+
+                    if conflicts_with_shared(previous_graph, name, version, shared) {
+                        log::debug!(
+                            "dependency {name}@{version} from old dependency graph uses versions other members don't, resolving it again",
+                        );
                         continue;
                     }
 
@@ -62,6 +220,10 @@ impl Project {
                         },
                         true,
                     );
+                    self.report(ProgressEvent::Resolved {
+                        name: name.clone(),
+                        version_id: version.clone(),
+                    });
 
                     let mut queue = node
                         .dependencies
@@ -89,6 +251,10 @@ impl Project {
                                 dep_node.clone(),
                                 false,
                             );
+                            self.report(ProgressEvent::Resolved {
+                                name: dep_name.clone(),
+                                version_id: dep_version.clone(),
+                            });
 
                             dep_node
                                 .dependencies
@@ -187,24 +353,36 @@ impl Project {
                 DependencySpecifiers::Workspace(_) => {
                     PackageSources::Workspace(crate::source::workspace::WorkspacePackageSource)
                 }
+                DependencySpecifiers::Path(_) => {
+                    PackageSources::Path(crate::source::path::PathPackageSource)
+                }
+                DependencySpecifiers::Url(specifier) => PackageSources::Url(
+                    crate::source::url::UrlPackageSource::new(specifier.url.clone()),
+                ),
+                DependencySpecifiers::Custom(specifier) => PackageSources::Custom(
+                    crate::source::custom::CustomPackageSource::new(specifier.custom.clone()),
+                ),
             };
 
             if refreshed_sources.insert(source.clone()) {
-                source.refresh(self).map_err(|e| Box::new(e.into()))?;
+                source.refresh(self, reqwest).map_err(|e| Box::new(e.into()))?;
             }
 
             let (name, resolved) = source
-                .resolve(&specifier, self, target)
+                .resolve(&specifier, self, reqwest, target)
                 .map_err(|e| Box::new(e.into()))?;
 
-            let Some(target_version_id) = graph
-                .get(&name)
-                .and_then(|versions| {
-                    versions
-                        .keys()
-                        // only consider versions that are compatible with the specifier
-                        .filter(|ver| resolved.contains_key(ver))
-                        .max()
+            // versions picked by other workspace members win, so that members don't drift apart
+            let Some(target_version_id) = [shared, &graph]
+                .into_iter()
+                .find_map(|graph| {
+                    graph.get(&name).and_then(|versions| {
+                        versions
+                            .keys()
+                            // only consider versions that are compatible with the specifier
+                            .filter(|ver| resolved.contains_key(ver))
+                            .max()
+                    })
                 })
                 .or_else(|| resolved.last_key_value().map(|(ver, _)| ver))
                 .cloned()
@@ -280,6 +458,11 @@ impl Project {
                 depth == 0,
             );
 
+            self.report(ProgressEvent::Resolved {
+                name: name.clone(),
+                version_id: target_version_id.clone(),
+            });
+
             log::debug!(
                 "{}resolved {}@{} from new dependency graph",
                 "\t".repeat(depth),
@@ -378,4 +561,20 @@ pub mod errors {
         #[error("no matching version found for {0}")]
         NoMatchingVersion(String),
     }
+
+    /// Errors that can occur when creating the dependency graphs of a workspace
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum WorkspaceDependencyGraphError {
+        /// An error occurred while finding the workspace members
+        #[error("error finding workspace members")]
+        WorkspaceMembers(#[from] crate::errors::WorkspaceMembersError),
+
+        /// An error occurred while creating a member's dependency graph
+        #[error("error creating dependency graph for workspace member at {0}")]
+        Member(
+            relative_path::RelativePathBuf,
+            #[source] Box<DependencyGraphError>,
+        ),
+    }
 }
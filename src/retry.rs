@@ -0,0 +1,333 @@
+use reqwest::{
+    blocking::{RequestBuilder, Response},
+    StatusCode,
+};
+use std::{
+    io::Read,
+    time::{Duration, Instant},
+};
+
+/// How failed downloads are retried
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    retries: u32,
+    backoff: Duration,
+    request_timeout: Option<Duration>,
+    total_timeout: Option<Duration>,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            retries: 3,
+            backoff: Duration::from_millis(500),
+            request_timeout: None,
+            total_timeout: None,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Create a new `RetryConfig`
+    pub fn new() -> Self {
+        RetryConfig::default()
+    }
+
+    /// Set how many times a failed download is retried
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set how long to wait before the first retry, which doubles with every following one
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the timeout of a single attempt, instead of the client's default
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout of a download, including all of its attempts
+    pub fn with_total_timeout(mut self, timeout: Duration) -> Self {
+        self.total_timeout = Some(timeout);
+        self
+    }
+
+    /// How many times a failed download is retried
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// How long to wait before the first retry
+    pub fn backoff(&self) -> Duration {
+        self.backoff
+    }
+
+    /// The timeout of a single attempt, if set
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    /// The timeout of a download, if set
+    pub fn total_timeout(&self) -> Option<Duration> {
+        self.total_timeout
+    }
+
+    /// Downloads the body of the request built by `request`, retrying transient failures. The request must be
+    /// idempotent, as it may be sent multiple times. `progress` is called with the number of bytes received so far
+    /// in the current attempt, and the size of the body if known
+    pub fn download<F: Fn() -> RequestBuilder, P: FnMut(u64, Option<u64>)>(
+        &self,
+        url: &str,
+        request: F,
+        mut progress: P,
+    ) -> Result<Vec<u8>, errors::RetryError> {
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            attempt += 1;
+
+            let remaining = self
+                .total_timeout
+                .map(|total| total.saturating_sub(start.elapsed()));
+            let timeout = match (self.request_timeout, remaining) {
+                (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+                (timeout, remaining) => timeout.or(remaining),
+            };
+
+            let mut builder = request();
+            if let Some(timeout) = timeout {
+                builder = builder.timeout(timeout);
+            }
+
+            let e = match read_body(builder, &mut progress) {
+                Ok(bytes) => return Ok(bytes),
+                Err(e) => e,
+            };
+
+            if attempt > self.retries || !is_transient(&e) {
+                return Err(errors::RetryError::Request(url.to_string(), attempt, e));
+            }
+
+            let backoff = self
+                .backoff
+                .saturating_mul(2u32.saturating_pow(attempt - 1));
+
+            if self
+                .total_timeout
+                .is_some_and(|total| start.elapsed() + backoff >= total)
+            {
+                return Err(errors::RetryError::TimedOut(url.to_string(), attempt, e));
+            }
+
+            log::warn!("attempt {attempt} to download {url} failed, retrying in {backoff:?}: {e}");
+            std::thread::sleep(backoff);
+        }
+    }
+}
+
+/// Sends a request, and reads its body in chunks, reporting each to `progress`
+fn read_body<P: FnMut(u64, Option<u64>)>(
+    builder: RequestBuilder,
+    progress: &mut P,
+) -> Result<Vec<u8>, errors::AttemptError> {
+    let mut response = builder.send().and_then(Response::error_for_status)?;
+    let total = response.content_length();
+
+    // the length is only a hint, so don't let a server make us reserve more than a reasonable amount up front
+    let mut bytes = Vec::with_capacity(total.unwrap_or(0).min(16 * 1024 * 1024) as usize);
+    let mut chunk = vec![0; 64 * 1024];
+
+    loop {
+        let read = response.read(&mut chunk)?;
+        if read == 0 {
+            return Ok(bytes);
+        }
+
+        bytes.extend_from_slice(&chunk[..read]);
+        progress(bytes.len() as u64, total);
+    }
+}
+
+/// Whether an attempt that failed with this error may succeed if tried again
+fn is_transient(e: &errors::AttemptError) -> bool {
+    let e = match e {
+        errors::AttemptError::Request(e) => e,
+        // the connection broke, or timed out, while reading the body
+        errors::AttemptError::Body(e) => {
+            return matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::UnexpectedEof
+            )
+        }
+    };
+
+    match e.status() {
+        Some(status) => status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+        None => e.is_connect() || e.is_timeout(),
+    }
+}
+
+/// Errors that can occur when retrying downloads
+pub mod errors {
+    use thiserror::Error;
+
+    /// Errors that can occur when downloading with retries
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RetryError {
+        /// The download failed, and either couldn't be retried or ran out of retries
+        #[error("error downloading {0} after {1} attempt(s)")]
+        Request(String, u32, #[source] AttemptError),
+
+        /// The download didn't complete within the total timeout
+        #[error("timed out downloading {0} after {1} attempt(s)")]
+        TimedOut(String, u32, #[source] AttemptError),
+    }
+
+    /// Errors that can occur in a single download attempt
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum AttemptError {
+        /// Sending the request failed, or the server responded with an error
+        #[error(transparent)]
+        Request(#[from] reqwest::Error),
+
+        /// Reading the response body failed
+        #[error("error reading response body")]
+        Body(#[from] std::io::Error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::Write,
+        net::{TcpListener, TcpStream},
+    };
+
+    fn response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn read_request(stream: &mut TcpStream) {
+        let mut request = vec![];
+        let mut buf = [0; 1024];
+
+        while !request.ends_with(b"\r\n\r\n") {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(read) => request.extend_from_slice(&buf[..read]),
+            }
+        }
+    }
+
+    /// Serves the responses in order, one per connection, returning the URL of the server. Connections an empty
+    /// response is served to are kept open without ever being responded to
+    fn serve(responses: Vec<String>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        std::thread::spawn(move || {
+            let mut unanswered = vec![];
+
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                read_request(&mut stream);
+
+                if response.is_empty() {
+                    unanswered.push(stream);
+                } else {
+                    stream.write_all(response.as_bytes()).unwrap();
+                }
+            }
+        });
+
+        url
+    }
+
+    fn download(config: &RetryConfig, url: &str) -> Result<Vec<u8>, errors::RetryError> {
+        let client = reqwest::blocking::Client::new();
+        config.download(url, || client.get(url), |_, _| {})
+    }
+
+    fn config() -> RetryConfig {
+        RetryConfig::new().with_backoff(Duration::from_millis(1))
+    }
+
+    #[test]
+    fn retries_until_the_server_succeeds() {
+        let url = serve(vec![
+            response("503 Service Unavailable", ""),
+            response("429 Too Many Requests", ""),
+            response("500 Internal Server Error", ""),
+            response("200 OK", "hello"),
+        ]);
+
+        assert_eq!(download(&config(), &url).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn retries_attempts_which_time_out() {
+        let url = serve(vec![String::new(), response("200 OK", "hello")]);
+
+        let config = config().with_request_timeout(Duration::from_millis(200));
+        assert_eq!(download(&config, &url).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn gives_up_after_the_last_retry() {
+        let url = serve(vec![response("503 Service Unavailable", ""); 3]);
+
+        let err = download(&config().with_retries(2), &url).unwrap_err();
+        assert!(matches!(err, errors::RetryError::Request(_, 3, _)));
+    }
+
+    #[test]
+    fn doesnt_retry_client_errors() {
+        for status in ["404 Not Found", "408 Request Timeout"] {
+            let url = serve(vec![response(status, ""), response("200 OK", "hello")]);
+
+            let err = download(&config(), &url).unwrap_err();
+            assert!(matches!(err, errors::RetryError::Request(_, 1, _)));
+        }
+    }
+
+    #[test]
+    fn stops_once_the_total_timeout_expires() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        // accepts connections without ever responding
+        std::thread::spawn(move || {
+            let mut streams = vec![];
+            for stream in listener.incoming() {
+                streams.push(stream);
+            }
+        });
+
+        let start = Instant::now();
+        let err = download(
+            &config()
+                .with_backoff(Duration::from_millis(200))
+                .with_total_timeout(Duration::from_millis(500)),
+            &url,
+        )
+        .unwrap_err();
+
+        assert!(matches!(err, errors::RetryError::TimedOut(..)));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,522 @@
+use crate::{
+    lockfile::{DownloadedGraph, Lockfile},
+    names::PackageNames,
+    source::{
+        pesde::{IndexFile, IndexFileEntry, PesdePackageSource},
+        refs::PackageRefs,
+        version_id::VersionId,
+    },
+    Project,
+};
+use serde_json::{json, Value};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+/// The format of a software bill of materials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5, as JSON
+    CycloneDxJson,
+    /// SPDX 2.3, as JSON
+    SpdxJson,
+}
+
+impl Display for SbomFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SbomFormat::CycloneDxJson => write!(f, "cyclonedx-json"),
+            SbomFormat::SpdxJson => write!(f, "spdx-json"),
+        }
+    }
+}
+
+impl FromStr for SbomFormat {
+    type Err = errors::SbomFormatFromStr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cyclonedx-json" => Ok(SbomFormat::CycloneDxJson),
+            "spdx-json" => Ok(SbomFormat::SpdxJson),
+            _ => Err(errors::SbomFormatFromStr::Unknown(s.to_string())),
+        }
+    }
+}
+
+struct Component<'a> {
+    name: &'a PackageNames,
+    version_id: &'a VersionId,
+    pkg_ref: &'a PackageRefs,
+    dependencies: Vec<(&'a PackageNames, &'a VersionId)>,
+    metadata: Option<IndexFileEntry>,
+}
+
+impl Component<'_> {
+    fn id(&self) -> String {
+        format!("{}@{}", self.name, self.version_id.escaped())
+    }
+
+    fn location(&self) -> String {
+        match self.pkg_ref {
+            PackageRefs::Pesde(pkg_ref) => pkg_ref.index_url.to_bstring().to_string(),
+            #[cfg(feature = "wally-compat")]
+            PackageRefs::Wally(pkg_ref) => pkg_ref.index_url.to_bstring().to_string(),
+            PackageRefs::Git(pkg_ref) => {
+                format!("{}#{}", pkg_ref.repo.to_bstring(), pkg_ref.tree_id)
+            }
+            PackageRefs::Workspace(pkg_ref) => pkg_ref.path.to_string(),
+            PackageRefs::Path(pkg_ref) => pkg_ref.path.to_string(),
+            PackageRefs::Url(pkg_ref) => pkg_ref.url.to_string(),
+            PackageRefs::Custom(pkg_ref) => format!("custom:{}", pkg_ref.custom),
+        }
+    }
+
+    /// The package URL of this component
+    fn purl(&self) -> String {
+        let (ty, mut qualifiers) = match self.pkg_ref {
+            PackageRefs::Pesde(pkg_ref) => (
+                "pesde",
+                vec![("repository_url", pkg_ref.index_url.to_bstring().to_string())],
+            ),
+            #[cfg(feature = "wally-compat")]
+            PackageRefs::Wally(pkg_ref) => (
+                "wally",
+                vec![("repository_url", pkg_ref.index_url.to_bstring().to_string())],
+            ),
+            PackageRefs::Git(pkg_ref) => (
+                "generic",
+                vec![(
+                    "vcs_url",
+                    format!("git+{}@{}", pkg_ref.repo.to_bstring(), pkg_ref.tree_id),
+                )],
+            ),
+            PackageRefs::Url(pkg_ref) => {
+                ("generic", vec![("download_url", pkg_ref.url.to_string())])
+            }
+            PackageRefs::Workspace(_) | PackageRefs::Path(_) | PackageRefs::Custom(_) => {
+                ("generic", vec![])
+            }
+        };
+        qualifiers.push(("target", self.version_id.target().to_string()));
+        qualifiers.sort_by_key(|(key, _)| *key);
+
+        let (scope, name) = self.name.as_str();
+
+        format!(
+            "pkg:{ty}/{}/{}@{}?{}",
+            purl_encode(scope),
+            purl_encode(name),
+            purl_encode(&self.version_id.version().to_string()),
+            qualifiers
+                .iter()
+                .map(|(key, value)| format!("{key}={}", purl_encode(value)))
+                .collect::<Vec<_>>()
+                .join("&")
+        )
+    }
+}
+
+/// Percent-encodes a package URL component
+fn purl_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| {
+            if byte.is_ascii_alphanumeric() || b".-_~".contains(&byte) {
+                (byte as char).to_string()
+            } else {
+                format!("%{byte:02X}")
+            }
+        })
+        .collect()
+}
+
+/// Turns a package id into an SPDX identifier. Characters SPDX identifiers can't contain, and the `-` used as
+/// the escape character, are written as `-XX` hex bytes so that different ids never map to the same identifier
+fn spdx_id(id: &str) -> String {
+    let mut spdx_id = "SPDXRef-Package-".to_string();
+
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' {
+            spdx_id.push(byte as char);
+        } else {
+            spdx_id.push_str(&format!("-{byte:02X}"));
+        }
+    }
+
+    spdx_id
+}
+
+/// Whether a license is a syntactically valid SPDX license expression
+fn is_spdx_expression(license: &str) -> bool {
+    fn is_id(word: &str) -> bool {
+        let is_part = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        };
+
+        match word.split_once(':') {
+            Some((document, license)) => {
+                document.starts_with("DocumentRef-")
+                    && license.starts_with("LicenseRef-")
+                    && is_part(document)
+                    && is_part(license)
+            }
+            None => is_part(word.strip_suffix('+').unwrap_or(word)),
+        }
+    }
+
+    fn is_operator(word: &str, operator: &str) -> bool {
+        word == operator || word == operator.to_lowercase()
+    }
+
+    // expression := term (("AND" | "OR") term)*
+    // term := (id | "(" expression ")") ("WITH" id)?
+    fn expression<'a>(words: &mut std::iter::Peekable<impl Iterator<Item = &'a str>>) -> bool {
+        loop {
+            match words.next() {
+                Some("(") => {
+                    if !expression(words) || words.next() != Some(")") {
+                        return false;
+                    }
+                }
+                Some(word) if is_id(word) => {}
+                _ => return false,
+            }
+
+            if words.next_if(|word| is_operator(word, "WITH")).is_some()
+                && !words
+                    .next()
+                    .is_some_and(|word| is_id(word) && !word.ends_with('+'))
+            {
+                return false;
+            }
+
+            if words
+                .next_if(|word| is_operator(word, "AND") || is_operator(word, "OR"))
+                .is_none()
+            {
+                return true;
+            }
+        }
+    }
+
+    let spaced = license.replace('(', " ( ").replace(')', " ) ");
+    let mut words = spaced.split_whitespace().peekable();
+
+    expression(&mut words) && words.next().is_none()
+}
+
+impl Project {
+    /// Reads the metadata the local copies of the indices have about the packages in the graph
+    fn index_metadata(
+        &self,
+        graph: &DownloadedGraph,
+    ) -> HashMap<(PackageNames, VersionId), IndexFileEntry> {
+        let mut files = HashMap::<_, Option<IndexFile>>::new();
+        let mut metadata = HashMap::new();
+
+        for (name, versions) in graph {
+            for (version_id, node) in versions {
+                let (PackageNames::Pesde(pkg_name), PackageRefs::Pesde(pkg_ref)) =
+                    (name, &node.node.pkg_ref)
+                else {
+                    continue;
+                };
+
+                let file = files
+                    .entry((pkg_ref.index_url.clone(), pkg_name.clone()))
+                    .or_insert_with(|| {
+                        let source = PesdePackageSource::new(pkg_ref.index_url.clone());
+                        let (scope, name) = pkg_name.as_str();
+
+                        match source.read_local_index_file(&[scope, name], self) {
+                            Ok(Some(s)) => toml::from_str(&s)
+                                .inspect_err(|e| {
+                                    log::warn!("failed to parse index file for {pkg_name}: {e}")
+                                })
+                                .ok(),
+                            Ok(None) => None,
+                            Err(e) => {
+                                log::warn!("failed to read index file for {pkg_name}: {e}");
+                                None
+                            }
+                        }
+                    });
+
+                if let Some(entry) = file.as_ref().and_then(|file| file.get(version_id)) {
+                    metadata.insert((name.clone(), version_id.clone()), entry.clone());
+                }
+            }
+        }
+
+        metadata
+    }
+
+    /// Generates a software bill of materials for the packages in a lockfile, including those of workspace members sharing it
+    pub fn sbom(&self, lockfile: &Lockfile, format: SbomFormat) -> Value {
+        let graph = &lockfile.graph;
+        let mut metadata = self.index_metadata(graph);
+
+        let components = graph
+            .iter()
+            .flat_map(|(name, versions)| {
+                versions
+                    .iter()
+                    .map(move |(version_id, node)| (name, version_id, node))
+            })
+            .map(|(name, version_id, node)| Component {
+                name,
+                version_id,
+                pkg_ref: &node.node.pkg_ref,
+                dependencies: node
+                    .node
+                    .dependencies
+                    .iter()
+                    .map(|(name, (version_id, _))| (name, version_id))
+                    .collect(),
+                metadata: metadata.remove(&(name.clone(), version_id.clone())),
+            })
+            .collect::<Vec<_>>();
+
+        let direct = graph
+            .iter()
+            .flat_map(|(name, versions)| {
+                versions
+                    .iter()
+                    .filter(|(_, node)| node.node.direct.is_some())
+                    .map(move |(version_id, _)| format!("{name}@{}", version_id.escaped()))
+            })
+            .collect::<Vec<_>>();
+
+        let root_id = format!("{}@{}", lockfile.name, lockfile.version);
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let tool = concat!("pesde-", env!("CARGO_PKG_VERSION"));
+
+        match format {
+            SbomFormat::CycloneDxJson => {
+                let mut dependencies = components
+                    .iter()
+                    .map(|component| {
+                        json!({
+                            "ref": component.id(),
+                            "dependsOn": component
+                                .dependencies
+                                .iter()
+                                .map(|(name, version_id)| format!("{name}@{}", version_id.escaped()))
+                                .collect::<Vec<_>>(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                dependencies.push(json!({ "ref": root_id, "dependsOn": direct }));
+
+                json!({
+                    "bomFormat": "CycloneDX",
+                    "specVersion": "1.5",
+                    "version": 1,
+                    "metadata": {
+                        "timestamp": timestamp,
+                        "tools": [{ "vendor": "pesde", "name": "pesde", "version": env!("CARGO_PKG_VERSION") }],
+                        "component": {
+                            "type": "application",
+                            "bom-ref": root_id,
+                            "name": lockfile.name.to_string(),
+                            "version": lockfile.version.to_string(),
+                        },
+                    },
+                    "components": components
+                        .iter()
+                        .map(|component| {
+                            let mut value = json!({
+                                "type": "library",
+                                "bom-ref": component.id(),
+                                "name": component.name.to_string(),
+                                "version": component.version_id.version().to_string(),
+                                "purl": component.purl(),
+                                "properties": [
+                                    { "name": "pesde:target", "value": component.version_id.target().to_string() },
+                                    { "name": "pesde:source", "value": component.location() },
+                                ],
+                            });
+
+                            if let Some(metadata) = &component.metadata {
+                                if let Some(description) = &metadata.description {
+                                    value["description"] = json!(description);
+                                }
+
+                                if let Some(license) = &metadata.license {
+                                    value["licenses"] = if is_spdx_expression(license) {
+                                        json!([{ "expression": license }])
+                                    } else {
+                                        json!([{ "license": { "name": license } }])
+                                    };
+                                }
+
+                                if !metadata.authors.is_empty() {
+                                    value["author"] = json!(metadata.authors.join(", "));
+                                }
+
+                                if let Some(repository) = &metadata.repository {
+                                    value["externalReferences"] =
+                                        json!([{ "type": "vcs", "url": repository.to_string() }]);
+                                }
+                            }
+
+                            value
+                        })
+                        .collect::<Vec<_>>(),
+                    "dependencies": dependencies,
+                })
+            }
+            SbomFormat::SpdxJson => {
+                let root_spdx_id = spdx_id(&root_id);
+
+                let mut packages = vec![json!({
+                    "SPDXID": root_spdx_id,
+                    "name": lockfile.name.to_string(),
+                    "versionInfo": lockfile.version.to_string(),
+                    "downloadLocation": "NOASSERTION",
+                    "filesAnalyzed": false,
+                    "primaryPackagePurpose": "APPLICATION",
+                })];
+
+                let mut relationships = vec![json!({
+                    "spdxElementId": "SPDXRef-DOCUMENT",
+                    "relationshipType": "DESCRIBES",
+                    "relatedSpdxElement": root_spdx_id,
+                })];
+
+                relationships.extend(direct.iter().map(|id| {
+                    json!({
+                        "spdxElementId": root_spdx_id,
+                        "relationshipType": "DEPENDS_ON",
+                        "relatedSpdxElement": spdx_id(id),
+                    })
+                }));
+
+                for component in &components {
+                    let id = spdx_id(&component.id());
+                    let metadata = component.metadata.as_ref();
+
+                    let mut package = json!({
+                        "SPDXID": id,
+                        "name": component.name.to_string(),
+                        "versionInfo": component.version_id.version().to_string(),
+                        "downloadLocation": metadata
+                            .and_then(|metadata| metadata.repository.as_ref())
+                            .map(|repository| repository.to_string())
+                            .unwrap_or_else(|| "NOASSERTION".to_string()),
+                        "sourceInfo": format!("{} ({})", component.location(), component.version_id.target()),
+                        "licenseConcluded": "NOASSERTION",
+                        "licenseDeclared": metadata
+                            .and_then(|metadata| metadata.license.clone())
+                            .filter(|license| is_spdx_expression(license))
+                            .unwrap_or_else(|| "NOASSERTION".to_string()),
+                        "filesAnalyzed": false,
+                        "primaryPackagePurpose": "LIBRARY",
+                        "externalRefs": [{
+                            "referenceCategory": "PACKAGE-MANAGER",
+                            "referenceType": "purl",
+                            "referenceLocator": component.purl(),
+                        }],
+                    });
+
+                    if let Some(description) =
+                        metadata.and_then(|metadata| metadata.description.as_ref())
+                    {
+                        package["description"] = json!(description);
+                    }
+
+                    packages.push(package);
+
+                    relationships.extend(component.dependencies.iter().map(|(name, version_id)| {
+                        json!({
+                            "spdxElementId": id,
+                            "relationshipType": "DEPENDS_ON",
+                            "relatedSpdxElement": spdx_id(&format!("{name}@{}", version_id.escaped())),
+                        })
+                    }));
+                }
+
+                json!({
+                    "spdxVersion": "SPDX-2.3",
+                    "dataLicense": "CC0-1.0",
+                    "SPDXID": "SPDXRef-DOCUMENT",
+                    "name": root_id,
+                    "documentNamespace": format!(
+                        "https://spdx.org/spdxdocs/{}-{}-{}",
+                        lockfile.name,
+                        lockfile.version,
+                        crate::util::hash(format!("{root_id}{timestamp}"))
+                    ),
+                    "creationInfo": {
+                        "created": timestamp,
+                        "creators": [format!("Tool: {tool}")],
+                    },
+                    "packages": packages,
+                    "relationships": relationships,
+                })
+            }
+        }
+    }
+}
+
+/// Errors that can occur when generating a software bill of materials
+pub mod errors {
+    use thiserror::Error;
+
+    /// Errors that can occur when parsing an SBOM format from a string
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum SbomFormatFromStr {
+        /// The format is not known
+        #[error("unknown sbom format {0}, expected one of cyclonedx-json, spdx-json")]
+        Unknown(String),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spdx_ids_stay_distinct() {
+        let ids = [
+            "acme/foo@1.0.0 luau",
+            "acme-foo@1.0.0-luau",
+            "acme/foo@1.0.0-luau",
+        ]
+        .map(spdx_id);
+
+        assert_eq!(ids[0], "SPDXRef-Package-acme-2Ffoo-401.0.0-20luau");
+        assert_ne!(ids[0], ids[1]);
+        assert_ne!(ids[1], ids[2]);
+        assert_ne!(ids[0], ids[2]);
+    }
+
+    #[test]
+    fn validates_license_expressions() {
+        for license in [
+            "MIT",
+            "GPL-2.0+",
+            "MIT OR Apache-2.0",
+            "(MIT AND BSD-3-Clause) or Apache-2.0 WITH LLVM-exception",
+            "LicenseRef-custom",
+            "DocumentRef-spdx:LicenseRef-custom",
+        ] {
+            assert!(is_spdx_expression(license), "{license}");
+        }
+
+        for license in [
+            "",
+            "MIT License",
+            "see LICENSE.md",
+            "(MIT",
+            "MIT OR",
+            "MIT WITH",
+            "AND MIT",
+            "MIT)",
+        ] {
+            assert!(!is_spdx_expression(license), "{license}");
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use std::fmt::Debug;
+
+use pkg_ref::CustomPackageRef;
+use specifier::CustomDependencySpecifier;
+
+use crate::{
+    manifest::target::{Target, TargetKind},
+    source::{fs::PackageFS, PackageSource, ResolveResult},
+    Project,
+};
+
+/// The custom package reference
+pub mod pkg_ref;
+/// The custom dependency specifier
+pub mod specifier;
+
+/// The error type returned by custom sources
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A source of packages implemented outside of pesde, registered on a [`Project`] by name with
+/// [`Project::with_custom_sources`]
+pub trait CustomSource: Debug + Send + Sync {
+    /// Refreshes the source
+    fn refresh(
+        &self,
+        _project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), BoxedError> {
+        Ok(())
+    }
+
+    /// Resolves a specifier to references of the package's versions
+    fn resolve(
+        &self,
+        specifier: &CustomDependencySpecifier,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+        project_target: TargetKind,
+    ) -> Result<ResolveResult<CustomPackageRef>, BoxedError>;
+
+    /// Downloads a package, recording any information only known after downloading in the reference
+    fn download(
+        &self,
+        pkg_ref: &mut CustomPackageRef,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(PackageFS, Target), BoxedError>;
+}
+
+/// The package source dispatching to a custom source registered on the project
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+pub struct CustomPackageSource {
+    name: String,
+}
+
+impl CustomPackageSource {
+    /// Creates a new custom package source, dispatching to the custom source registered under the given name
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    /// The name the custom source is registered under
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get<'a>(&self, project: &'a Project) -> Option<&'a dyn CustomSource> {
+        project
+            .custom_sources
+            .get(&self.name)
+            .map(|source| source.as_ref())
+    }
+}
+
+impl PackageSource for CustomPackageSource {
+    type Specifier = CustomDependencySpecifier;
+    type Ref = CustomPackageRef;
+    type RefreshError = errors::RefreshError;
+    type ResolveError = errors::ResolveError;
+    type DownloadError = errors::DownloadError;
+
+    fn refresh(
+        &self,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
+        let source = self
+            .get(project)
+            .ok_or_else(|| errors::RefreshError::NotRegistered(self.name.clone()))?;
+
+        source
+            .refresh(project, reqwest)
+            .map_err(|e| errors::RefreshError::Refresh(self.name.clone(), e))
+    }
+
+    fn resolve(
+        &self,
+        specifier: &Self::Specifier,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+        project_target: TargetKind,
+    ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
+        let source = self
+            .get(project)
+            .ok_or_else(|| errors::ResolveError::NotRegistered(self.name.clone()))?;
+
+        let (name, mut versions) = source
+            .resolve(specifier, project, reqwest, project_target)
+            .map_err(|e| errors::ResolveError::Resolve(self.name.clone(), e))?;
+
+        // the source's name is what the reference is dispatched back to this source by
+        for pkg_ref in versions.values_mut() {
+            pkg_ref.custom.clone_from(&self.name);
+        }
+
+        Ok((name, versions))
+    }
+
+    fn download(
+        &self,
+        pkg_ref: &mut Self::Ref,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(PackageFS, Target), Self::DownloadError> {
+        let source = self
+            .get(project)
+            .ok_or_else(|| errors::DownloadError::NotRegistered(self.name.clone()))?;
+
+        source
+            .download(pkg_ref, project, reqwest)
+            .map_err(|e| errors::DownloadError::Download(self.name.clone(), e))
+    }
+}
+
+/// Errors that can occur when interacting with custom package sources
+pub mod errors {
+    use thiserror::Error;
+
+    use crate::source::custom::BoxedError;
+
+    /// Errors that can occur when refreshing a custom package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RefreshError {
+        /// No custom source is registered under the name
+        #[error("no custom source registered as {0}")]
+        NotRegistered(String),
+
+        /// The custom source failed to refresh
+        #[error("error refreshing custom source {0}")]
+        Refresh(String, #[source] BoxedError),
+    }
+
+    /// Errors that can occur when resolving a package from a custom package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum ResolveError {
+        /// No custom source is registered under the name
+        #[error("no custom source registered as {0}")]
+        NotRegistered(String),
+
+        /// The custom source failed to resolve the package
+        #[error("error resolving package from custom source {0}")]
+        Resolve(String, #[source] BoxedError),
+    }
+
+    /// Errors that can occur when downloading a package from a custom package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum DownloadError {
+        /// No custom source is registered under the name
+        #[error("no custom source registered as {0}")]
+        NotRegistered(String),
+
+        /// The custom source failed to download the package
+        #[error("error downloading package from custom source {0}")]
+        Download(String, #[source] BoxedError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        source::{refs::PackageRefs, specifiers::DependencySpecifiers, PackageSources},
+        AuthConfig,
+    };
+    use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+    /// Resolves the version in the specifier's data, and marks the references it downloads
+    #[derive(Debug)]
+    struct TestSource;
+
+    impl CustomSource for TestSource {
+        fn resolve(
+            &self,
+            specifier: &CustomDependencySpecifier,
+            _project: &Project,
+            _reqwest: &reqwest::blocking::Client,
+            project_target: TargetKind,
+        ) -> Result<ResolveResult<CustomPackageRef>, BoxedError> {
+            let version = specifier
+                .data
+                .get("version")
+                .and_then(toml::Value::as_str)
+                .ok_or("missing version")?;
+
+            Ok((
+                crate::names::PackageNames::Pesde("acme/foo".parse()?),
+                BTreeMap::from([(
+                    crate::source::version_id::VersionId::new(version.parse()?, project_target),
+                    CustomPackageRef {
+                        custom: String::new(),
+                        data: specifier.data.clone(),
+                        dependencies: Default::default(),
+                        target: Target::Luau {
+                            lib: None,
+                            bin: None,
+                        },
+                    },
+                )]),
+            ))
+        }
+
+        fn download(
+            &self,
+            pkg_ref: &mut CustomPackageRef,
+            _project: &Project,
+            _reqwest: &reqwest::blocking::Client,
+        ) -> Result<(PackageFS, Target), BoxedError> {
+            pkg_ref
+                .data
+                .insert("downloaded".to_string(), toml::Value::Boolean(true));
+
+            Ok((PackageFS::CAS(Default::default()), pkg_ref.target.clone()))
+        }
+    }
+
+    fn project(with_source: bool) -> Project {
+        let project = Project::new(
+            "project",
+            None::<PathBuf>,
+            "data",
+            "cas",
+            AuthConfig::default(),
+        );
+
+        if with_source {
+            project.with_custom_sources([(
+                "test".to_string(),
+                Arc::new(TestSource) as Arc<dyn CustomSource>,
+            )])
+        } else {
+            project
+        }
+    }
+
+    fn specifier() -> DependencySpecifiers {
+        toml::from_str("custom = \"test\"\nversion = \"1.0.0\"\nrevision = 3").unwrap()
+    }
+
+    #[test]
+    fn dispatches_to_the_registered_source() {
+        let project = project(true);
+        let reqwest = reqwest::blocking::Client::new();
+        let source = PackageSources::Custom(CustomPackageSource::new("test".to_string()));
+
+        let (name, versions) = source
+            .resolve(&specifier(), &project, &reqwest, TargetKind::Luau)
+            .unwrap();
+        assert_eq!(name.to_string(), "acme/foo");
+
+        let (version_id, mut pkg_ref) = versions.into_iter().next().unwrap();
+        assert_eq!(version_id.to_string(), "1.0.0 luau");
+
+        let PackageRefs::Custom(custom_ref) = &pkg_ref else {
+            panic!("expected a custom package reference, got {pkg_ref:?}");
+        };
+        // the source's name is filled in, so that the reference is dispatched back to it
+        assert_eq!(custom_ref.custom, "test");
+        assert_eq!(custom_ref.data["revision"], toml::Value::Integer(3));
+
+        source.download(&mut pkg_ref, &project, &reqwest).unwrap();
+        let PackageRefs::Custom(custom_ref) = &pkg_ref else {
+            unreachable!()
+        };
+        assert_eq!(custom_ref.data["downloaded"], toml::Value::Boolean(true));
+    }
+
+    #[test]
+    fn rejects_unregistered_sources() {
+        let project = project(false);
+        let reqwest = reqwest::blocking::Client::new();
+        let source = PackageSources::Custom(CustomPackageSource::new("test".to_string()));
+
+        assert!(matches!(
+            source.resolve(&specifier(), &project, &reqwest, TargetKind::Luau),
+            Err(crate::source::errors::ResolveError::Custom(
+                errors::ResolveError::NotRegistered(name)
+            )) if name == "test"
+        ));
+    }
+}
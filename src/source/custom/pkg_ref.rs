@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::{
+    manifest::{
+        target::{Target, TargetKind},
+        DependencyType,
+    },
+    source::{custom::CustomPackageSource, DependencySpecifiers, PackageRef, PackageSources},
+};
+
+/// A custom package reference
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CustomPackageRef {
+    /// The name the custom source is registered under. Set by pesde after resolving
+    pub custom: String,
+    /// The data the custom source needs to download the package, stored in the lockfile as-is
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub data: BTreeMap<String, toml::Value>,
+    /// The dependencies of the package
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
+    /// The target of the package
+    pub target: Target,
+}
+impl PackageRef for CustomPackageRef {
+    fn dependencies(&self) -> &BTreeMap<String, (DependencySpecifiers, DependencyType)> {
+        &self.dependencies
+    }
+
+    fn use_new_structure(&self) -> bool {
+        true
+    }
+
+    fn target_kind(&self) -> TargetKind {
+        self.target.kind()
+    }
+
+    fn source(&self) -> PackageSources {
+        PackageSources::Custom(CustomPackageSource::new(self.custom.clone()))
+    }
+}
@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use crate::source::DependencySpecifier;
+
+/// The specifier for a dependency on a custom source
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CustomDependencySpecifier {
+    /// The name the custom source is registered under
+    pub custom: String,
+    /// The fields interpreted by the custom source, such as the package's name and version
+    #[serde(flatten)]
+    // schemars has no schema for TOML values, and flattened maps don't restrict the other fields either way
+    #[schemars(with = "BTreeMap<String, String>")]
+    pub data: BTreeMap<String, toml::Value>,
+}
+impl DependencySpecifier for CustomDependencySpecifier {}
+
+// TOML values don't implement `Eq` or `Hash`, which specifiers need to be used as keys
+impl Eq for CustomDependencySpecifier {}
+
+impl Hash for CustomDependencySpecifier {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.custom.hash(state);
+
+        for (key, value) in &self.data {
+            key.hash(state);
+            value.to_string().hash(state);
+        }
+    }
+}
+
+impl Display for CustomDependencySpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.custom)?;
+
+        for (key, value) in &self.data {
+            match value {
+                toml::Value::String(value) => write!(f, " {key}={value}")?,
+                value => write!(f, " {key}={value}")?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -13,17 +13,218 @@ use relative_path::RelativePathBuf;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+/// How files are placed into a project from the CAS
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkStrategy {
+    /// Try reflinking, then hard linking, then fall back to copying
+    #[default]
+    Auto,
+    /// Reflink (copy-on-write clone) files, copying if the file system doesn't support it
+    Reflink,
+    /// Hard link files, copying if the CAS is on a different device
+    Hardlink,
+    /// Symlink files. Tools which resolve paths relative to the real location of a file
+    /// (such as `require`s in some runtimes) may not work with this strategy
+    Symlink,
+    /// Copy files
+    Copy,
+}
+
+impl std::fmt::Display for LinkStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LinkStrategy::Auto => write!(f, "auto"),
+            LinkStrategy::Reflink => write!(f, "reflink"),
+            LinkStrategy::Hardlink => write!(f, "hardlink"),
+            LinkStrategy::Symlink => write!(f, "symlink"),
+            LinkStrategy::Copy => write!(f, "copy"),
+        }
+    }
+}
+
+impl std::str::FromStr for LinkStrategy {
+    type Err = errors::LinkStrategyFromStr;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(LinkStrategy::Auto),
+            "reflink" => Ok(LinkStrategy::Reflink),
+            "hardlink" => Ok(LinkStrategy::Hardlink),
+            "symlink" => Ok(LinkStrategy::Symlink),
+            "copy" => Ok(LinkStrategy::Copy),
+            _ => Err(errors::LinkStrategyFromStr::Unknown(s.to_string())),
+        }
+    }
+}
+
+/// Falls back to copying the file if linking failed because the file system can't link it
+fn link_or_copy(
+    linked: std::io::Result<()>,
+    cas_path: &Path,
+    destination: &Path,
+) -> std::io::Result<()> {
+    match linked {
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::CrossesDevices | std::io::ErrorKind::Unsupported
+            ) =>
+        {
+            copy_file(cas_path, destination)
+        }
+        res => res,
+    }
+}
+
+impl LinkStrategy {
+    /// Places the file at `cas_path` at `destination` using this strategy
+    pub fn link<P: AsRef<Path>, Q: AsRef<Path>>(
+        self,
+        cas_path: P,
+        destination: Q,
+    ) -> std::io::Result<()> {
+        let (cas_path, destination) = (cas_path.as_ref(), destination.as_ref());
+
+        match self {
+            LinkStrategy::Auto => match reflink(cas_path, destination) {
+                Ok(()) => Ok(()),
+                Err(_) => LinkStrategy::Hardlink.link(cas_path, destination),
+            },
+            LinkStrategy::Reflink => match reflink(cas_path, destination) {
+                Ok(()) => Ok(()),
+                Err(_) => copy_file(cas_path, destination),
+            },
+            LinkStrategy::Hardlink => link_or_copy(
+                std::fs::hard_link(cas_path, destination),
+                cas_path,
+                destination,
+            ),
+            LinkStrategy::Symlink => {
+                #[cfg(unix)]
+                {
+                    std::os::unix::fs::symlink(cas_path, destination)
+                }
+                #[cfg(windows)]
+                {
+                    std::os::windows::fs::symlink_file(cas_path, destination)
+                }
+                #[cfg(not(any(unix, windows)))]
+                {
+                    copy_file(cas_path, destination)
+                }
+            }
+            LinkStrategy::Copy => copy_file(cas_path, destination),
+        }
+    }
+}
+
+/// Copies a file out of the CAS, making the copy writable
+fn copy_file(cas_path: &Path, destination: &Path) -> std::io::Result<()> {
+    let mut f = std::fs::File::create(destination)?;
+    f.write_all(&std::fs::read(cas_path)?)?;
+
+    let mut permissions = f.metadata()?.permissions();
+    #[cfg(windows)]
+    {
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o644);
+    }
+    f.set_permissions(permissions)
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(cas_path: &Path, destination: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let src = std::fs::File::open(cas_path)?;
+    let dst = std::fs::File::create_new(destination)?;
+
+    // SAFETY: both file descriptors are valid for the duration of the call
+    if unsafe { libc::ioctl(dst.as_raw_fd(), libc::FICLONE as _, src.as_raw_fd()) } == -1 {
+        let e = std::io::Error::last_os_error();
+        drop(dst);
+        std::fs::remove_file(destination)?;
+        return Err(e);
+    }
+
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn reflink(cas_path: &Path, destination: &Path) -> std::io::Result<()> {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let src = CString::new(cas_path.as_os_str().as_bytes())?;
+    let dst = CString::new(destination.as_os_str().as_bytes())?;
+
+    // SAFETY: both pointers are valid, NUL-terminated strings
+    if unsafe { libc::clonefile(src.as_ptr(), dst.as_ptr(), 0) } == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn reflink(_cas_path: &Path, _destination: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
 /// A file system entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "FSEntryRepr", into = "FSEntryRepr")]
 pub enum FSEntry {
-    /// A file with the given hash
+    /// A file with the given hash, and its Unix mode if it is executable
+    File(String, Option<u32>),
+    /// A directory
+    Directory,
+}
+
+// files without a mode are stored the same way as before modes were recorded,
+// so that index files stay readable by both older and newer versions
+#[derive(Serialize, Deserialize)]
+enum FSEntryRepr {
     #[serde(rename = "f")]
     File(String),
-    /// A directory
+    #[serde(rename = "x")]
+    ModeFile(String, u32),
     #[serde(rename = "d")]
     Directory,
 }
 
+impl From<FSEntryRepr> for FSEntry {
+    fn from(repr: FSEntryRepr) -> Self {
+        match repr {
+            FSEntryRepr::File(hash) => FSEntry::File(hash, None),
+            FSEntryRepr::ModeFile(hash, mode) => FSEntry::File(hash, Some(mode)),
+            FSEntryRepr::Directory => FSEntry::Directory,
+        }
+    }
+}
+
+impl From<FSEntry> for FSEntryRepr {
+    fn from(entry: FSEntry) -> Self {
+        match entry {
+            FSEntry::File(hash, None) => FSEntryRepr::File(hash),
+            FSEntry::File(hash, Some(mode)) => FSEntryRepr::ModeFile(hash, mode),
+            FSEntry::Directory => FSEntryRepr::Directory,
+        }
+    }
+}
+
+/// Returns the mode to record for a file with the given mode, which is only done for executable files
+pub(crate) fn file_mode(mode: u32) -> Option<u32> {
+    (mode & 0o111 != 0).then_some(mode & 0o777)
+}
+
 /// A package's file system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 // don't need to differentiate between CAS and non-CAS, since non-CAS won't be serialized
@@ -59,7 +260,8 @@ pub(crate) fn store_in_cas<P: AsRef<Path>>(
     Ok((hash, cas_path))
 }
 
-pub(crate) fn store_reader_in_cas<P: AsRef<Path>>(
+/// Stores the contents of a reader in the CAS, returning their hash
+pub fn store_reader_in_cas<P: AsRef<Path>>(
     cas_dir: P,
     contents: &mut dyn Read,
 ) -> std::io::Result<String> {
@@ -141,7 +343,7 @@ impl PackageFS {
         &self,
         destination: P,
         cas_path: Q,
-        link: bool,
+        link_strategy: LinkStrategy,
     ) -> std::io::Result<()> {
         match self {
             PackageFS::CAS(entries) => {
@@ -149,7 +351,7 @@ impl PackageFS {
                     let path = path.to_path(destination.as_ref());
 
                     match entry {
-                        FSEntry::File(hash) => {
+                        FSEntry::File(hash, mode) => {
                             if let Some(parent) = path.parent() {
                                 std::fs::create_dir_all(parent)?;
                             }
@@ -157,25 +359,25 @@ impl PackageFS {
                             let (prefix, rest) = hash.split_at(2);
                             let cas_file_path = cas_path.as_ref().join(prefix).join(rest);
 
-                            if link {
-                                std::fs::hard_link(cas_file_path, path)?;
-                            } else {
-                                let mut f = std::fs::File::create(&path)?;
-                                f.write_all(&std::fs::read(cas_file_path)?)?;
-
-                                let mut permissions = f.metadata()?.permissions();
-                                #[cfg(windows)]
-                                {
-                                    #[allow(clippy::permissions_set_readonly_false)]
-                                    permissions.set_readonly(false);
-                                }
-                                #[cfg(unix)]
-                                {
-                                    use std::os::unix::fs::PermissionsExt;
-                                    permissions.set_mode(permissions.mode() | 0o644);
-                                }
-                                f.set_permissions(permissions)?;
+                            // files with a mode need their own inode, as changing the mode of a hard or symbolic
+                            // link would change it for the file in the CAS, and every other project linking it
+                            #[cfg(unix)]
+                            if let Some(mode) = mode {
+                                use std::os::unix::fs::PermissionsExt;
+
+                                LinkStrategy::Reflink.link(cas_file_path, &path)?;
+
+                                // only the executable bits are applied, keeping the copy's own permissions
+                                let mut permissions = std::fs::metadata(&path)?.permissions();
+                                permissions.set_mode(permissions.mode() | (mode & 0o111));
+                                std::fs::set_permissions(&path, permissions)?;
+
+                                continue;
                             }
+                            #[cfg(not(unix))]
+                            let _ = mode;
+
+                            link_strategy.link(cas_file_path, &path)?;
                         }
                         FSEntry::Directory => {
                             std::fs::create_dir_all(path)?;
@@ -206,3 +408,209 @@ impl PackageFS {
         std::fs::read_to_string(cas_file_path).ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package_fs(entries: &[(&str, FSEntry)]) -> PackageFS {
+        PackageFS::CAS(
+            entries
+                .iter()
+                .map(|(path, entry)| (RelativePathBuf::from(*path), entry.clone()))
+                .collect(),
+        )
+    }
+
+    fn entries(fs: &PackageFS) -> &BTreeMap<RelativePathBuf, FSEntry> {
+        match fs {
+            PackageFS::CAS(entries) => entries,
+            PackageFS::Copy(..) => panic!("expected a CAS file system"),
+        }
+    }
+
+    #[test]
+    fn reads_index_files_without_modes() {
+        let fs = toml::from_str::<PackageFS>(
+            r#""init.luau" = { f = "abcdef" }
+src = "d"
+"#,
+        )
+        .unwrap();
+
+        let entries = entries(&fs);
+        assert!(matches!(
+            &entries[&RelativePathBuf::from("init.luau")],
+            FSEntry::File(hash, None) if hash == "abcdef"
+        ));
+        assert!(matches!(
+            entries[&RelativePathBuf::from("src")],
+            FSEntry::Directory
+        ));
+    }
+
+    #[test]
+    fn writes_files_without_modes_in_the_old_format() {
+        let fs = package_fs(&[("init.luau", FSEntry::File("abcdef".to_string(), None))]);
+
+        assert_eq!(
+            toml::Value::try_from(&fs).unwrap(),
+            toml::from_str::<toml::Value>(r#""init.luau" = { f = "abcdef" }"#).unwrap()
+        );
+    }
+
+    #[test]
+    fn round_trips_modes() {
+        let fs = package_fs(&[
+            ("bin/run", FSEntry::File("abcdef".to_string(), Some(0o755))),
+            ("init.luau", FSEntry::File("012345".to_string(), None)),
+        ]);
+
+        let fs = toml::from_str::<PackageFS>(&toml::to_string(&fs).unwrap()).unwrap();
+        let entries = entries(&fs);
+        assert!(matches!(
+            entries[&RelativePathBuf::from("bin/run")],
+            FSEntry::File(_, Some(0o755))
+        ));
+        assert!(matches!(
+            entries[&RelativePathBuf::from("init.luau")],
+            FSEntry::File(_, None)
+        ));
+    }
+
+    #[test]
+    fn only_records_modes_of_executables() {
+        assert_eq!(file_mode(0o100644), None);
+        assert_eq!(file_mode(0o100755), Some(0o755));
+        assert_eq!(file_mode(0o744), Some(0o744));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn executables_dont_share_the_cas_inode() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let dir = tempfile::tempdir().unwrap();
+        let cas_dir = dir.path().join("cas");
+        let (hash, cas_file) = store_in_cas(&cas_dir, b"#!/bin/sh\n").unwrap();
+        let (lib_hash, lib_cas_file) = store_in_cas(&cas_dir, b"return {}").unwrap();
+
+        let fs = package_fs(&[
+            ("bin/run", FSEntry::File(hash, Some(0o755))),
+            ("init.luau", FSEntry::File(lib_hash, None)),
+        ]);
+        let destination = dir.path().join("pkg");
+        fs.write_to(&destination, &cas_dir, LinkStrategy::Hardlink)
+            .unwrap();
+
+        let executable = std::fs::metadata(destination.join("bin/run")).unwrap();
+        assert_ne!(executable.permissions().mode() & 0o111, 0);
+
+        let cas_metadata = std::fs::metadata(cas_file).unwrap();
+        assert_eq!(cas_metadata.permissions().mode() & 0o111, 0);
+        assert_ne!(cas_metadata.ino(), executable.ino());
+
+        // files without a mode are still linked
+        let lib = std::fs::metadata(destination.join("init.luau")).unwrap();
+        assert_eq!(lib.ino(), std::fs::metadata(lib_cas_file).unwrap().ino());
+    }
+
+    #[test]
+    fn copies_files_which_cant_be_linked() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, cas_file) = store_in_cas(dir.path().join("cas"), b"return {}").unwrap();
+
+        for kind in [
+            std::io::ErrorKind::CrossesDevices,
+            std::io::ErrorKind::Unsupported,
+        ] {
+            let destination = dir.path().join(format!("{kind:?}.luau"));
+            link_or_copy(Err(kind.into()), &cas_file, &destination).unwrap();
+
+            assert_eq!(std::fs::read(&destination).unwrap(), b"return {}");
+            assert!(!std::fs::metadata(&destination)
+                .unwrap()
+                .permissions()
+                .readonly());
+        }
+
+        // other errors aren't hidden by copying
+        let destination = dir.path().join("denied.luau");
+        let e = link_or_copy(
+            Err(std::io::ErrorKind::PermissionDenied.into()),
+            &cas_file,
+            &destination,
+        )
+        .unwrap_err();
+        assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn reflinks_or_copies_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, cas_file) = store_in_cas(dir.path().join("cas"), b"return {}").unwrap();
+
+        let destination = dir.path().join("init.luau");
+        LinkStrategy::Reflink.link(&cas_file, &destination).unwrap();
+
+        assert_eq!(std::fs::read(&destination).unwrap(), b"return {}");
+        assert!(!std::fs::symlink_metadata(&destination)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_, cas_file) = store_in_cas(dir.path().join("cas"), b"return {}").unwrap();
+
+        let destination = dir.path().join("init.luau");
+        LinkStrategy::Symlink.link(&cas_file, &destination).unwrap();
+
+        assert!(std::fs::symlink_metadata(&destination)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_link(&destination).unwrap(), cas_file);
+    }
+
+    #[test]
+    fn round_trips_link_strategies() {
+        for strategy in [
+            LinkStrategy::Auto,
+            LinkStrategy::Reflink,
+            LinkStrategy::Hardlink,
+            LinkStrategy::Symlink,
+            LinkStrategy::Copy,
+        ] {
+            assert_eq!(
+                strategy.to_string().parse::<LinkStrategy>().unwrap(),
+                strategy
+            );
+        }
+
+        assert!(matches!(
+            "softlink".parse::<LinkStrategy>(),
+            Err(errors::LinkStrategyFromStr::Unknown(s)) if s == "softlink"
+        ));
+    }
+}
+
+/// Errors that can occur when interacting with the file system of packages
+pub mod errors {
+    use thiserror::Error;
+
+    /// Errors that can occur when parsing a link strategy from a string
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum LinkStrategyFromStr {
+        /// The link strategy is unknown
+        #[error(
+            "unknown link strategy {0}, expected one of auto, reflink, hardlink, symlink, copy"
+        )]
+        Unknown(String),
+    }
+}
@@ -1,7 +1,7 @@
 use std::{collections::BTreeMap, fmt::Debug, hash::Hash, path::PathBuf};
 
 use gix::{bstr::BStr, traverse::tree::Recorder, Url};
-use relative_path::RelativePathBuf;
+use relative_path::{RelativePath, RelativePathBuf};
 
 use crate::{
     manifest::{
@@ -10,8 +10,11 @@ use crate::{
     },
     names::PackageNames,
     source::{
-        fs::{store_in_cas, FSEntry, PackageFS},
-        git::{pkg_ref::GitPackageRef, specifier::GitDependencySpecifier},
+        fs::{file_mode, store_in_cas, FSEntry, PackageFS},
+        git::{
+            pkg_ref::GitPackageRef,
+            specifier::{GitDependencySpecifier, GitVersion},
+        },
         git_index::GitBasedSource,
         specifiers::DependencySpecifiers,
         PackageSource, ResolveResult, VersionId, IGNORED_DIRS, IGNORED_FILES,
@@ -55,6 +58,202 @@ impl GitPackageSource {
     }
 }
 
+impl GitPackageSource {
+    /// Stores the contents of a tree in the CAS, including the contents of the submodules it contains
+    fn store_tree(
+        &self,
+        repo: &gix::Repository,
+        tree: &gix::Tree,
+        root_tree: &gix::Tree,
+        prefix: &RelativePath,
+        project: &Project,
+        entries: &mut BTreeMap<RelativePathBuf, FSEntry>,
+    ) -> Result<(), errors::DownloadError> {
+        let mut recorder = Recorder::default();
+        tree.traverse()
+            .breadthfirst(&mut recorder)
+            .map_err(|e| errors::DownloadError::TraverseTree(Box::new(self.repo_url.clone()), e))?;
+
+        let mut gitmodules = None;
+
+        for entry in recorder.records {
+            let relative_path = RelativePathBuf::from(entry.filepath.to_string());
+            let path = prefix.join(&relative_path);
+
+            if entry.mode.is_commit() {
+                let gitmodules = match &mut gitmodules {
+                    Some(gitmodules) => gitmodules,
+                    None => gitmodules.insert(self.read_gitmodules(root_tree)?),
+                };
+
+                self.store_submodule(
+                    gitmodules,
+                    root_tree,
+                    &relative_path,
+                    entry.oid,
+                    &path,
+                    project,
+                    entries,
+                )?;
+
+                continue;
+            }
+
+            let object = repo.find_object(entry.oid).map_err(|e| {
+                errors::DownloadError::ParseEntryToObject(Box::new(self.repo_url.clone()), e)
+            })?;
+
+            if matches!(object.kind, gix::object::Kind::Tree) {
+                if path
+                    .components()
+                    .next()
+                    .is_some_and(|ct| IGNORED_DIRS.contains(&ct.as_str()))
+                {
+                    continue;
+                }
+
+                entries.insert(path, FSEntry::Directory);
+
+                continue;
+            }
+
+            if IGNORED_FILES.contains(&path.as_str()) {
+                continue;
+            }
+
+            let data = object.into_blob().data.clone();
+            let hash = store_in_cas(project.cas_dir(), &data)?.0;
+
+            entries.insert(path, FSEntry::File(hash, file_mode(entry.mode.0.into())));
+        }
+
+        Ok(())
+    }
+
+    fn read_gitmodules(
+        &self,
+        root_tree: &gix::Tree,
+    ) -> Result<gix::submodule::File, errors::DownloadError> {
+        let mut buf = vec![];
+        let Some(entry) = root_tree
+            .lookup_entry_by_path(".gitmodules", &mut buf)
+            .map_err(|e| {
+                errors::DownloadError::ParseEntryToObject(Box::new(self.repo_url.clone()), e)
+            })?
+        else {
+            return Err(errors::DownloadError::NoGitmodules(Box::new(
+                self.repo_url.clone(),
+            )));
+        };
+
+        let object = entry.object().map_err(|e| {
+            errors::DownloadError::ParseEntryToObject(Box::new(self.repo_url.clone()), e)
+        })?;
+
+        gix::submodule::File::from_bytes(&object.data, None, &Default::default())
+            .map_err(|e| errors::DownloadError::ParseGitmodules(Box::new(self.repo_url.clone()), e))
+    }
+
+    /// Fetches the commit a gitlink points to and stores its contents in the CAS
+    #[allow(clippy::too_many_arguments)]
+    fn store_submodule(
+        &self,
+        gitmodules: &gix::submodule::File,
+        root_tree: &gix::Tree,
+        relative_path: &RelativePath,
+        commit: gix::ObjectId,
+        path: &RelativePath,
+        project: &Project,
+        entries: &mut BTreeMap<RelativePathBuf, FSEntry>,
+    ) -> Result<(), errors::DownloadError> {
+        // the gitlink's path is relative to the package, which may be nested in the repository
+        let mut buf = vec![];
+        let name = gitmodules
+            .names()
+            .find(|name| {
+                gitmodules.path(name).is_ok_and(|module_path| {
+                    let module_path = RelativePathBuf::from(module_path.to_string());
+
+                    module_path.ends_with(relative_path)
+                        && root_tree
+                            .lookup_entry_by_path(module_path.as_str(), &mut buf)
+                            .is_ok_and(|entry| {
+                                entry.is_some_and(|entry| entry.object_id() == commit)
+                            })
+                })
+            })
+            .ok_or_else(|| {
+                errors::DownloadError::NoSubmodule(
+                    relative_path.to_relative_path_buf(),
+                    Box::new(self.repo_url.clone()),
+                )
+            })?;
+
+        let source = GitPackageSource::new(self.submodule_url(gitmodules, name)?);
+        GitBasedSource::refresh(&source, project).map_err(|e| {
+            errors::DownloadError::RefreshSubmodule(Box::new(source.repo_url.clone()), Box::new(e))
+        })?;
+
+        let repo = gix::open(source.path(project)).map_err(|e| {
+            errors::DownloadError::OpenRepo(Box::new(source.repo_url.clone()), Box::new(e))
+        })?;
+        let tree = repo
+            .find_object(commit)
+            .map_err(|e| {
+                errors::DownloadError::ParseEntryToObject(Box::new(source.repo_url.clone()), e)
+            })?
+            .peel_to_tree()
+            .map_err(|e| {
+                errors::DownloadError::ParseObjectToTree(Box::new(source.repo_url.clone()), e)
+            })?;
+
+        entries.insert(path.to_relative_path_buf(), FSEntry::Directory);
+
+        source.store_tree(&repo, &tree, &tree, path, project, entries)
+    }
+
+    /// The URL of a submodule, with relative URLs resolved against this repository's URL like Git does
+    fn submodule_url(
+        &self,
+        gitmodules: &gix::submodule::File,
+        name: &BStr,
+    ) -> Result<Url, errors::DownloadError> {
+        let raw = gitmodules
+            .config()
+            .string(format!("submodule.{name}.url"))
+            .map(|url| url.to_string());
+
+        match raw {
+            Some(raw) if raw.starts_with("./") || raw.starts_with("../") => {
+                let mut url = self.repo_url.clone();
+                let mut segments = url
+                    .path
+                    .to_string()
+                    .trim_end_matches('/')
+                    .split('/')
+                    .map(|segment| segment.to_string())
+                    .collect::<Vec<_>>();
+
+                for segment in raw.split('/') {
+                    match segment {
+                        "" | "." => {}
+                        ".." => {
+                            segments.pop();
+                        }
+                        segment => segments.push(segment.to_string()),
+                    }
+                }
+
+                url.path = segments.join("/").into();
+                Ok(url)
+            }
+            _ => gitmodules.url(name).map_err(|e| {
+                errors::DownloadError::SubmoduleUrl(Box::new(self.repo_url.clone()), e)
+            }),
+        }
+    }
+}
+
 impl PackageSource for GitPackageSource {
     type Specifier = GitDependencySpecifier;
     type Ref = GitPackageRef;
@@ -62,7 +261,11 @@ impl PackageSource for GitPackageSource {
     type ResolveError = errors::ResolveError;
     type DownloadError = errors::DownloadError;
 
-    fn refresh(&self, project: &Project) -> Result<(), Self::RefreshError> {
+    fn refresh(
+        &self,
+        project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
         GitBasedSource::refresh(self, project)
     }
 
@@ -70,20 +273,34 @@ impl PackageSource for GitPackageSource {
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        _reqwest: &reqwest::blocking::Client,
         _project_target: TargetKind,
     ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
-        let repo = gix::open(self.path(project))
-            .map_err(|e| errors::ResolveError::OpenRepo(Box::new(self.repo_url.clone()), e))?;
+        let repo = gix::open(self.path(project)).map_err(|e| {
+            errors::ResolveError::OpenRepo(Box::new(self.repo_url.clone()), Box::new(e))
+        })?;
         let rev = repo
-            .rev_parse_single(BStr::new(&specifier.rev))
+            .rev_parse_single(BStr::new(&specifier.version.rev_spec()))
             .map_err(|e| {
                 errors::ResolveError::ParseRev(
-                    specifier.rev.clone(),
+                    specifier.version.to_string(),
                     Box::new(self.repo_url.clone()),
-                    e,
+                    Box::new(e),
                 )
             })?;
 
+        let commit = rev
+            .object()
+            .map_err(|e| {
+                errors::ResolveError::ParseRevToObject(Box::new(self.repo_url.clone()), e)
+            })?
+            .peel_to_kind(gix::object::Kind::Commit)
+            .map_err(|e| {
+                errors::ResolveError::ParseObjectToCommit(Box::new(self.repo_url.clone()), e)
+            })?
+            .id
+            .to_string();
+
         // TODO: possibly use the search algorithm from src/main.rs to find the workspace root
 
         let root_tree = rev
@@ -195,6 +412,8 @@ impl PackageSource for GitPackageSource {
                                 );
                             }
                             DependencySpecifiers::Git(_) => {}
+                            DependencySpecifiers::Url(_) => {}
+                            DependencySpecifiers::Custom(_) => {}
                             DependencySpecifiers::Workspace(specifier) => {
                                 let lockfile = self
                                     .read_file(
@@ -210,12 +429,12 @@ impl PackageSource for GitPackageSource {
                                     })?;
 
                                 let lockfile = match lockfile {
-                                    Some(l) => match toml::from_str::<crate::Lockfile>(&l) {
+                                    Some(l) => match l.parse::<crate::Lockfile>() {
                                         Ok(l) => l,
                                         Err(e) => {
                                             return Err(errors::ResolveError::DeserLockfile(
                                                 Box::new(self.repo_url.clone()),
-                                                e,
+                                                Box::new(e),
                                             ))
                                         }
                                     },
@@ -243,7 +462,27 @@ impl PackageSource for GitPackageSource {
 
                                 spec = DependencySpecifiers::Git(GitDependencySpecifier {
                                     repo: self.repo_url.clone(),
-                                    rev: rev.to_string(),
+                                    version: GitVersion::Rev(commit.clone()),
+                                    path: Some(path),
+                                })
+                            }
+                            DependencySpecifiers::Path(path_spec) => {
+                                let path = specifier
+                                    .path
+                                    .clone()
+                                    .unwrap_or_default()
+                                    .join_normalized(&path_spec.path);
+
+                                if path.starts_with("..") {
+                                    return Err(errors::ResolveError::PathOutsideRepository(
+                                        path_spec.path.clone(),
+                                        Box::new(self.repo_url.clone()),
+                                    ));
+                                }
+
+                                spec = DependencySpecifiers::Git(GitDependencySpecifier {
+                                    repo: self.repo_url.clone(),
+                                    version: GitVersion::Rev(commit.clone()),
                                     path: Some(path),
                                 })
                             }
@@ -262,7 +501,7 @@ impl PackageSource for GitPackageSource {
             None => {
                 match self
                     .read_file(
-                        [crate::source::wally::compat_util::WALLY_MANIFEST_FILE_NAME],
+                        [crate::source::wally::manifest::WALLY_MANIFEST_FILE_NAME],
                         project,
                         Some(tree.clone()),
                     )
@@ -317,6 +556,7 @@ impl PackageSource for GitPackageSource {
                 GitPackageRef {
                     repo: self.repo_url.clone(),
                     tree_id: tree.id.to_string(),
+                    commit: Some(commit),
                     target,
                     new_structure,
                     dependencies,
@@ -327,15 +567,12 @@ impl PackageSource for GitPackageSource {
 
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         _reqwest: &reqwest::blocking::Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError> {
-        let index_file = project
-            .cas_dir
-            .join("git_index")
-            .join(hash(self.as_bytes()))
-            .join(&pkg_ref.tree_id);
+        let index_file = pkg_ref.index_file(project);
+        let _lock = project.lock_path(&index_file)?;
 
         match std::fs::read_to_string(&index_file) {
             Ok(s) => {
@@ -353,7 +590,7 @@ impl PackageSource for GitPackageSource {
                 let manifest = match &fs {
                     PackageFS::CAS(entries) => {
                         match entries.get(&RelativePathBuf::from(MANIFEST_FILE_NAME)) {
-                            Some(FSEntry::File(hash)) => match fs
+                            Some(FSEntry::File(hash, _)) => match fs
                                 .read_file(hash, project.cas_dir())
                                 .map(|m| toml::de::from_str::<Manifest>(&m))
                             {
@@ -377,7 +614,11 @@ impl PackageSource for GitPackageSource {
                     #[cfg(feature = "wally-compat")]
                     None if !pkg_ref.new_structure => {
                         let tempdir = tempfile::tempdir()?;
-                        fs.write_to(tempdir.path(), project.cas_dir(), false)?;
+                        fs.write_to(
+                            tempdir.path(),
+                            project.cas_dir(),
+                            crate::source::fs::LinkStrategy::Copy,
+                        )?;
 
                         crate::source::wally::compat_util::get_target(project, &tempdir)?
                     }
@@ -394,15 +635,16 @@ impl PackageSource for GitPackageSource {
             Err(e) => return Err(errors::DownloadError::Io(e)),
         }
 
-        let repo = gix::open(self.path(project))
-            .map_err(|e| errors::DownloadError::OpenRepo(Box::new(self.repo_url.clone()), e))?;
+        let repo = gix::open(self.path(project)).map_err(|e| {
+            errors::DownloadError::OpenRepo(Box::new(self.repo_url.clone()), Box::new(e))
+        })?;
         let rev = repo
             .rev_parse_single(BStr::new(&pkg_ref.tree_id))
             .map_err(|e| {
                 errors::DownloadError::ParseRev(
                     pkg_ref.tree_id.clone(),
                     Box::new(self.repo_url.clone()),
-                    e,
+                    Box::new(e),
                 )
             })?;
         let tree = rev
@@ -415,47 +657,49 @@ impl PackageSource for GitPackageSource {
                 errors::DownloadError::ParseObjectToTree(Box::new(self.repo_url.clone()), e)
             })?;
 
-        let mut recorder = Recorder::default();
-        tree.traverse()
-            .breadthfirst(&mut recorder)
-            .map_err(|e| errors::DownloadError::TraverseTree(Box::new(self.repo_url.clone()), e))?;
+        // submodules are configured at the root of the repository, which the package may be nested in
+        let root_tree = match &pkg_ref.commit {
+            Some(commit) => Some(
+                repo.rev_parse_single(BStr::new(commit))
+                    .map_err(|e| {
+                        errors::DownloadError::ParseRev(
+                            commit.clone(),
+                            Box::new(self.repo_url.clone()),
+                            Box::new(e),
+                        )
+                    })?
+                    .object()
+                    .map_err(|e| {
+                        errors::DownloadError::ParseEntryToObject(
+                            Box::new(self.repo_url.clone()),
+                            e,
+                        )
+                    })?
+                    .peel_to_tree()
+                    .map_err(|e| {
+                        errors::DownloadError::ParseObjectToTree(Box::new(self.repo_url.clone()), e)
+                    })?,
+            ),
+            None => None,
+        };
 
         let mut entries = BTreeMap::new();
-        let mut manifest = None;
-
-        for entry in recorder.records {
-            let path = RelativePathBuf::from(entry.filepath.to_string());
-            let object = repo.find_object(entry.oid).map_err(|e| {
-                errors::DownloadError::ParseEntryToObject(Box::new(self.repo_url.clone()), e)
-            })?;
-
-            if matches!(object.kind, gix::object::Kind::Tree) {
-                if path
-                    .components()
-                    .next()
-                    .is_some_and(|ct| IGNORED_DIRS.contains(&ct.as_str()))
-                {
-                    continue;
-                }
-
-                entries.insert(path, FSEntry::Directory);
-
-                continue;
-            }
-
-            if IGNORED_FILES.contains(&path.as_str()) {
-                continue;
-            }
-
-            let data = object.into_blob().data.clone();
-            let hash = store_in_cas(project.cas_dir(), &data)?.0;
-
-            if path == MANIFEST_FILE_NAME {
-                manifest = Some(data);
+        self.store_tree(
+            &repo,
+            &tree,
+            root_tree.as_ref().unwrap_or(&tree),
+            RelativePath::new(""),
+            project,
+            &mut entries,
+        )?;
+
+        let manifest = match entries.get(&RelativePathBuf::from(MANIFEST_FILE_NAME)) {
+            Some(FSEntry::File(hash, _)) => {
+                let (prefix, rest) = hash.split_at(2);
+                Some(std::fs::read(project.cas_dir().join(prefix).join(rest))?)
             }
-
-            entries.insert(path, FSEntry::File(hash));
-        }
+            _ => None,
+        };
 
         let manifest = match manifest {
             Some(data) => match String::from_utf8(data.to_vec()) {
@@ -480,7 +724,11 @@ impl PackageSource for GitPackageSource {
             #[cfg(feature = "wally-compat")]
             None if !pkg_ref.new_structure => {
                 let tempdir = tempfile::tempdir()?;
-                fs.write_to(tempdir.path(), project.cas_dir(), false)?;
+                fs.write_to(
+                    tempdir.path(),
+                    project.cas_dir(),
+                    crate::source::fs::LinkStrategy::Copy,
+                )?;
 
                 crate::source::wally::compat_util::get_target(project, &tempdir)?
             }
@@ -507,6 +755,119 @@ impl PackageSource for GitPackageSource {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthConfig;
+    use std::{path::Path, process::Command};
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=pesde", "-c", "user.email=pesde@localhost"])
+            .args(["-c", "protocol.file.allow=always"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
+
+    fn file_url(path: &Path) -> Url {
+        format!("file://{}", path.display())
+            .as_str()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn downloads_submodules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        let sub = root.join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        git(&sub, &["init", "-q"]);
+        std::fs::write(sub.join("lib.luau"), "return 1").unwrap();
+        git(&sub, &["add", "."]);
+        git(&sub, &["commit", "-q", "-m", "sub"]);
+
+        let pkg = root.join("pkg");
+        std::fs::create_dir(&pkg).unwrap();
+        git(&pkg, &["init", "-q"]);
+        std::fs::write(
+            pkg.join(MANIFEST_FILE_NAME),
+            "name = \"acme/pkg\"\nversion = \"0.1.0\"\n\n[target]\nenvironment = \"luau\"\nlib = \"init.luau\"\n\n[scripts]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            pkg.join("init.luau"),
+            "return require(\"./vendor/sub/lib\")",
+        )
+        .unwrap();
+        // relative URLs are resolved against the URL of the repository the package is in
+        git(&pkg, &["submodule", "add", "-q", "../sub", "vendor/sub"]);
+        git(&pkg, &["add", "."]);
+        git(&pkg, &["commit", "-q", "-m", "pkg"]);
+        let commit = git(&pkg, &["rev-parse", "HEAD"]);
+
+        let project_dir = root.join("project");
+        std::fs::create_dir(&project_dir).unwrap();
+        let project = Project::new(
+            &project_dir,
+            None::<PathBuf>,
+            root.join("data"),
+            root.join("cas"),
+            AuthConfig::default(),
+        );
+        let reqwest = reqwest::blocking::Client::new();
+
+        let source = GitPackageSource::new(file_url(&pkg));
+        PackageSource::refresh(&source, &project, &reqwest).unwrap();
+
+        let specifier = GitDependencySpecifier {
+            repo: file_url(&pkg),
+            version: GitVersion::Rev(commit.clone()),
+            path: None,
+        };
+        let (name, versions) = source
+            .resolve(&specifier, &project, &reqwest, TargetKind::Luau)
+            .unwrap();
+        assert_eq!(name.to_string(), "acme/pkg");
+
+        let (version_id, mut pkg_ref) = versions.into_iter().next().unwrap();
+        assert_eq!(version_id.to_string(), "0.1.0 luau");
+        assert_eq!(pkg_ref.commit.as_deref(), Some(commit.as_str()));
+
+        let (PackageFS::CAS(entries), _) =
+            source.download(&mut pkg_ref, &project, &reqwest).unwrap()
+        else {
+            panic!("expected a CAS file system");
+        };
+        assert!(matches!(
+            entries.get(&RelativePathBuf::from("vendor/sub/lib.luau")),
+            Some(FSEntry::File(..))
+        ));
+        assert!(matches!(
+            entries.get(&RelativePathBuf::from("init.luau")),
+            Some(FSEntry::File(..))
+        ));
+
+        // the cached file system is used the second time around
+        let (PackageFS::CAS(cached), _) =
+            source.download(&mut pkg_ref, &project, &reqwest).unwrap()
+        else {
+            panic!("expected a CAS file system");
+        };
+        assert_eq!(cached.len(), entries.len());
+    }
+}
+
 /// Errors that can occur when interacting with the Git package source
 pub mod errors {
     use crate::manifest::target::TargetKind;
@@ -519,14 +880,14 @@ pub mod errors {
     pub enum ResolveError {
         /// An error occurred opening the Git repository
         #[error("error opening Git repository for url {0}")]
-        OpenRepo(Box<gix::Url>, #[source] gix::open::Error),
+        OpenRepo(Box<gix::Url>, #[source] Box<gix::open::Error>),
 
         /// An error occurred parsing rev
         #[error("error parsing rev {0} for repository {1}")]
         ParseRev(
             String,
             Box<gix::Url>,
-            #[source] gix::revision::spec::parse::single::Error,
+            #[source] Box<gix::revision::spec::parse::single::Error>,
         ),
 
         /// An error occurred parsing rev to object
@@ -537,6 +898,10 @@ pub mod errors {
         #[error("error parsing object to tree for repository {0}")]
         ParseObjectToTree(Box<gix::Url>, #[source] gix::object::peel::to_kind::Error),
 
+        /// An error occurred parsing object to commit
+        #[error("error parsing object to commit for repository {0}")]
+        ParseObjectToCommit(Box<gix::Url>, #[source] gix::object::peel::to_kind::Error),
+
         /// An error occurred reading the manifest
         #[error("error reading manifest of repository {0}")]
         ReadManifest(
@@ -592,7 +957,10 @@ pub mod errors {
 
         /// An error occurred while deserializing the lockfile
         #[error("error deserializing lockfile for repository {0}")]
-        DeserLockfile(Box<gix::Url>, #[source] toml::de::Error),
+        DeserLockfile(
+            Box<gix::Url>,
+            #[source] Box<crate::errors::LockfileReadError>,
+        ),
 
         /// The repository is missing a lockfile
         #[error("no lockfile found in repository {0}")]
@@ -601,6 +969,10 @@ pub mod errors {
         /// No path for a workspace member was found in the lockfile
         #[error("no path found for workspace member {0} {1} in lockfile for repository {2}")]
         NoPathForWorkspaceMember(String, TargetKind, Box<gix::Url>),
+
+        /// A path dependency points outside of the repository
+        #[error("path dependency {0} points outside of repository {1}")]
+        PathOutsideRepository(RelativePathBuf, Box<gix::Url>),
     }
 
     /// Errors that can occur when downloading a package from a Git package source
@@ -626,14 +998,14 @@ pub mod errors {
 
         /// An error occurred opening the Git repository
         #[error("error opening Git repository for url {0}")]
-        OpenRepo(Box<gix::Url>, #[source] gix::open::Error),
+        OpenRepo(Box<gix::Url>, #[source] Box<gix::open::Error>),
 
         /// An error occurred parsing rev
         #[error("error parsing rev {0} for repository {1}")]
         ParseRev(
             String,
             Box<gix::Url>,
-            #[source] gix::revision::spec::parse::single::Error,
+            #[source] Box<gix::revision::spec::parse::single::Error>,
         ),
 
         /// An error occurred while traversing the tree
@@ -666,5 +1038,28 @@ pub mod errors {
         /// An error occurred while serializing the index file
         #[error("error serializing the index file for repository {0}")]
         SerializeIndex(Box<gix::Url>, #[source] toml::ser::Error),
+
+        /// The package contains submodules, but the repository has no `.gitmodules` file
+        #[error("no .gitmodules file found in repository {0}")]
+        NoGitmodules(Box<gix::Url>),
+
+        /// An error occurred parsing the `.gitmodules` file
+        #[error("error parsing .gitmodules file of repository {0}")]
+        ParseGitmodules(Box<gix::Url>, #[source] gix::config::parse::Error),
+
+        /// No submodule is configured for a gitlink
+        #[error("no submodule configured for {0} in repository {1}")]
+        NoSubmodule(RelativePathBuf, Box<gix::Url>),
+
+        /// The URL of a submodule is invalid
+        #[error("invalid submodule url in repository {0}")]
+        SubmoduleUrl(Box<gix::Url>, #[source] gix::submodule::config::url::Error),
+
+        /// An error occurred fetching a submodule's repository
+        #[error("error fetching submodule repository {0}")]
+        RefreshSubmodule(
+            Box<gix::Url>,
+            #[source] Box<crate::source::git_index::errors::RefreshError>,
+        ),
     }
 }
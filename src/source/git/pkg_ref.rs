@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use crate::{
     manifest::{target::TargetKind, DependencyType},
     source::{git::GitPackageSource, DependencySpecifiers, PackageRef, PackageSources},
+    util::hash,
+    Project,
 };
 
 /// A Git package reference
@@ -17,6 +19,9 @@ pub struct GitPackageRef {
     pub repo: gix::Url,
     /// The id of the package's tree
     pub tree_id: String,
+    /// The commit the package was resolved from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
     /// The dependencies of the package
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
@@ -25,6 +30,17 @@ pub struct GitPackageRef {
     /// The target of the package
     pub target: TargetKind,
 }
+impl GitPackageRef {
+    /// The path of the cached file system of this package
+    pub(crate) fn index_file(&self, project: &Project) -> PathBuf {
+        project
+            .cas_dir
+            .join("git_index")
+            .join(hash(GitPackageSource::new(self.repo.clone()).as_bytes()))
+            .join(&self.tree_id)
+    }
+}
+
 impl PackageRef for GitPackageRef {
     fn dependencies(&self) -> &BTreeMap<String, (DependencySpecifiers, DependencyType)> {
         &self.dependencies
@@ -15,8 +15,9 @@ pub struct GitDependencySpecifier {
     )]
 	#[schemars(with = "String")]
     pub repo: gix::Url,
-    /// The revision of the package
-    pub rev: String,
+    /// The version of the package in the repository
+    #[serde(flatten)]
+    pub version: GitVersion,
     /// The path of the package in the repository
     #[serde(default, skip_serializing_if = "Option::is_none")]
 	#[schemars(with = "Option<std::path::PathBuf>")]
@@ -26,6 +27,78 @@ impl DependencySpecifier for GitDependencySpecifier {}
 
 impl Display for GitDependencySpecifier {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}#{}", self.repo, self.rev)
+        write!(f, "{}#{}", self.repo, self.version)
+    }
+}
+
+/// The version of a Git dependency
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GitVersion {
+    /// A branch, which is followed when updating
+    Branch(String),
+    /// A tag
+    Tag(String),
+    /// Any revision Git can parse, such as a commit SHA
+    Rev(String),
+}
+
+impl GitVersion {
+    /// The revision to parse in the local copy of the repository
+    pub fn rev_spec(&self) -> String {
+        match self {
+            // branches are fetched as remote-tracking branches, the local ones aren't updated
+            GitVersion::Branch(branch) => format!("refs/remotes/origin/{branch}"),
+            GitVersion::Tag(tag) => format!("refs/tags/{tag}"),
+            GitVersion::Rev(rev) => rev.clone(),
+        }
+    }
+}
+
+// the derived schema disallows additional properties, which would reject the rest of the specifier it's flattened into
+impl JsonSchema for GitVersion {
+    fn schema_name() -> String {
+        "GitVersion".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        schemars::schema::SchemaObject {
+            subschemas: Some(Box::new(schemars::schema::SubschemaValidation {
+                one_of: Some(
+                    ["branch", "tag", "rev"]
+                        .into_iter()
+                        .map(|field| {
+                            schemars::schema::SchemaObject {
+                                instance_type: Some(schemars::schema::InstanceType::Object.into()),
+                                object: Some(Box::new(schemars::schema::ObjectValidation {
+                                    required: [field.to_string()].into(),
+                                    properties: [(
+                                        field.to_string(),
+                                        gen.subschema_for::<String>(),
+                                    )]
+                                    .into(),
+                                    ..Default::default()
+                                })),
+                                ..Default::default()
+                            }
+                            .into()
+                        })
+                        .collect(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+impl Display for GitVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GitVersion::Branch(branch) => write!(f, "{branch}"),
+            GitVersion::Tag(tag) => write!(f, "{tag}"),
+            GitVersion::Rev(rev) => write!(f, "{rev}"),
+        }
     }
 }
@@ -104,9 +104,12 @@ pub trait GitBasedSource {
         Ok(Some(string))
     }
 
-    /// Refreshes the repository
+    /// Refreshes the repository, fetching from its mirror if one is configured
     fn refresh(&self, project: &Project) -> Result<(), errors::RefreshError> {
         let path = self.path(project);
+        let url = project.mirror_config.rewrite_url(self.repo_url());
+        let _lock = project.lock_path(&path)?;
+
         if path.exists() {
             let repo = match gix::open(&path) {
                 Ok(repo) => repo,
@@ -122,21 +125,33 @@ pub trait GitBasedSource {
                 }
             };
 
-            let mut connection = remote.connect(Direction::Fetch).map_err(|e| {
-                errors::RefreshError::Connect(self.repo_url().to_string(), Box::new(e))
-            })?;
+            // the mirror may have changed since the repository was cloned, so fetch from it into the same refs
+            let remote = if remote.url(Direction::Fetch) == Some(&url) {
+                remote
+            } else {
+                let refspecs = remote
+                    .refspecs(Direction::Fetch)
+                    .iter()
+                    .map(|spec| spec.to_ref().to_bstring())
+                    .collect::<Vec<_>>();
+
+                repo.remote_at(url.clone())
+                    .map_err(|e| errors::RefreshError::RemoteAt(url.to_string(), Box::new(e)))?
+                    .with_refspecs(refspecs, Direction::Fetch)
+                    .map_err(|e| errors::RefreshError::RefSpecs(url.to_string(), Box::new(e)))?
+            };
+
+            let mut connection = remote
+                .connect(Direction::Fetch)
+                .map_err(|e| errors::RefreshError::Connect(url.to_string(), Box::new(e)))?;
 
             authenticate_conn(&mut connection, &project.auth_config);
 
             connection
                 .prepare_fetch(gix::progress::Discard, Default::default())
-                .map_err(|e| {
-                    errors::RefreshError::PrepareFetch(self.repo_url().to_string(), Box::new(e))
-                })?
+                .map_err(|e| errors::RefreshError::PrepareFetch(url.to_string(), Box::new(e)))?
                 .receive(gix::progress::Discard, &false.into())
-                .map_err(|e| {
-                    errors::RefreshError::Read(self.repo_url().to_string(), Box::new(e))
-                })?;
+                .map_err(|e| errors::RefreshError::Read(url.to_string(), Box::new(e)))?;
 
             return Ok(());
         }
@@ -145,14 +160,14 @@ pub trait GitBasedSource {
 
         let auth_config = project.auth_config.clone();
 
-        gix::prepare_clone_bare(self.repo_url().clone(), &path)
-            .map_err(|e| errors::RefreshError::Clone(self.repo_url().to_string(), Box::new(e)))?
+        gix::prepare_clone_bare(url.clone(), &path)
+            .map_err(|e| errors::RefreshError::Clone(url.to_string(), Box::new(e)))?
             .configure_connection(move |c| {
                 authenticate_conn(c, &auth_config);
                 Ok(())
             })
             .fetch_only(gix::progress::Discard, &false.into())
-            .map_err(|e| errors::RefreshError::Fetch(self.repo_url().to_string(), Box::new(e)))?;
+            .map_err(|e| errors::RefreshError::Fetch(url.to_string(), Box::new(e)))?;
 
         Ok(())
     }
@@ -184,6 +199,14 @@ pub mod errors {
         #[error("error getting default remote from repository at {0}")]
         GetDefaultRemote(PathBuf, #[source] Box<gix::remote::find::existing::Error>),
 
+        /// Error creating remote for the mirror of the repository
+        #[error("error creating remote for repository at {0}")]
+        RemoteAt(String, #[source] Box<gix::remote::init::Error>),
+
+        /// Error using the refspecs of the repository for its mirror
+        #[error("error using refspecs for repository at {0}")]
+        RefSpecs(String, #[source] Box<gix::refspec::parse::Error>),
+
         /// Error connecting to remote repository
         #[error("error connecting to remote repository at {0}")]
         Connect(String, #[source] Box<gix::remote::connect::Error>),
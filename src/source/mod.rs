@@ -3,6 +3,7 @@ use std::{collections::BTreeMap, fmt::Debug};
 use crate::{
     manifest::target::{Target, TargetKind},
     names::PackageNames,
+    progress::ProgressEvent,
     source::{
         fs::PackageFS, refs::PackageRefs, specifiers::DependencySpecifiers, traits::*,
         version_id::VersionId,
@@ -10,12 +11,16 @@ use crate::{
     Project,
 };
 
+/// Custom package sources
+pub mod custom;
 /// Packages' filesystems
 pub mod fs;
 /// The Git package source
 pub mod git;
 /// Git index-based package source utilities
 pub mod git_index;
+/// The path package source
+pub mod path;
 /// The pesde package source
 pub mod pesde;
 /// Package references
@@ -24,6 +29,8 @@ pub mod refs;
 pub mod specifiers;
 /// Traits for sources and packages
 pub mod traits;
+/// The URL package source
+pub mod url;
 /// Version IDs
 pub mod version_id;
 /// The Wally package source
@@ -53,6 +60,12 @@ pub enum PackageSources {
     Git(git::GitPackageSource),
     /// A workspace package source
     Workspace(workspace::WorkspacePackageSource),
+    /// A path package source
+    Path(path::PathPackageSource),
+    /// A URL package source
+    Url(url::UrlPackageSource),
+    /// A custom package source
+    Custom(custom::CustomPackageSource),
 }
 
 impl PackageSource for PackageSources {
@@ -62,25 +75,41 @@ impl PackageSource for PackageSources {
     type ResolveError = errors::ResolveError;
     type DownloadError = errors::DownloadError;
 
-    fn refresh(&self, project: &Project) -> Result<(), Self::RefreshError> {
-        match self {
-            PackageSources::Pesde(source) => source.refresh(project).map_err(Into::into),
+    fn refresh(
+        &self,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
+        project.report(ProgressEvent::RefreshStarted(self.clone()));
+
+        let result = match self {
+            PackageSources::Pesde(source) => source.refresh(project, reqwest).map_err(Into::into),
             #[cfg(feature = "wally-compat")]
-            PackageSources::Wally(source) => source.refresh(project).map_err(Into::into),
-            PackageSources::Git(source) => source.refresh(project).map_err(Into::into),
-            PackageSources::Workspace(source) => source.refresh(project).map_err(Into::into),
-        }
+            PackageSources::Wally(source) => source.refresh(project, reqwest).map_err(Into::into),
+            PackageSources::Git(source) => source.refresh(project, reqwest).map_err(Into::into),
+            PackageSources::Workspace(source) => {
+                source.refresh(project, reqwest).map_err(Into::into)
+            }
+            PackageSources::Path(source) => source.refresh(project, reqwest).map_err(Into::into),
+            PackageSources::Url(source) => source.refresh(project, reqwest).map_err(Into::into),
+            PackageSources::Custom(source) => source.refresh(project, reqwest).map_err(Into::into),
+        };
+
+        project.report(ProgressEvent::RefreshFinished(self.clone()));
+
+        result
     }
 
     fn resolve(
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        reqwest: &reqwest::blocking::Client,
         package_target: TargetKind,
     ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
         match (self, specifier) {
             (PackageSources::Pesde(source), DependencySpecifiers::Pesde(specifier)) => source
-                .resolve(specifier, project, package_target)
+                .resolve(specifier, project, reqwest, package_target)
                 .map(|(name, results)| {
                     (
                         name,
@@ -94,7 +123,7 @@ impl PackageSource for PackageSources {
 
             #[cfg(feature = "wally-compat")]
             (PackageSources::Wally(source), DependencySpecifiers::Wally(specifier)) => source
-                .resolve(specifier, project, package_target)
+                .resolve(specifier, project, reqwest, package_target)
                 .map(|(name, results)| {
                     (
                         name,
@@ -107,7 +136,7 @@ impl PackageSource for PackageSources {
                 .map_err(Into::into),
 
             (PackageSources::Git(source), DependencySpecifiers::Git(specifier)) => source
-                .resolve(specifier, project, package_target)
+                .resolve(specifier, project, reqwest, package_target)
                 .map(|(name, results)| {
                     (
                         name,
@@ -121,7 +150,7 @@ impl PackageSource for PackageSources {
 
             (PackageSources::Workspace(source), DependencySpecifiers::Workspace(specifier)) => {
                 source
-                    .resolve(specifier, project, package_target)
+                    .resolve(specifier, project, reqwest, package_target)
                     .map(|(name, results)| {
                         (
                             name,
@@ -136,13 +165,52 @@ impl PackageSource for PackageSources {
                     .map_err(Into::into)
             }
 
+            (PackageSources::Path(source), DependencySpecifiers::Path(specifier)) => source
+                .resolve(specifier, project, reqwest, package_target)
+                .map(|(name, results)| {
+                    (
+                        name,
+                        results
+                            .into_iter()
+                            .map(|(version, pkg_ref)| (version, PackageRefs::Path(pkg_ref)))
+                            .collect(),
+                    )
+                })
+                .map_err(Into::into),
+
+            (PackageSources::Url(source), DependencySpecifiers::Url(specifier)) => source
+                .resolve(specifier, project, reqwest, package_target)
+                .map(|(name, results)| {
+                    (
+                        name,
+                        results
+                            .into_iter()
+                            .map(|(version, pkg_ref)| (version, PackageRefs::Url(pkg_ref)))
+                            .collect(),
+                    )
+                })
+                .map_err(Into::into),
+
+            (PackageSources::Custom(source), DependencySpecifiers::Custom(specifier)) => source
+                .resolve(specifier, project, reqwest, package_target)
+                .map(|(name, results)| {
+                    (
+                        name,
+                        results
+                            .into_iter()
+                            .map(|(version, pkg_ref)| (version, PackageRefs::Custom(pkg_ref)))
+                            .collect(),
+                    )
+                })
+                .map_err(Into::into),
+
             _ => Err(errors::ResolveError::Mismatch),
         }
     }
 
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         reqwest: &reqwest::blocking::Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError> {
@@ -164,6 +232,18 @@ impl PackageSource for PackageSources {
                 .download(pkg_ref, project, reqwest)
                 .map_err(Into::into),
 
+            (PackageSources::Path(source), PackageRefs::Path(pkg_ref)) => source
+                .download(pkg_ref, project, reqwest)
+                .map_err(Into::into),
+
+            (PackageSources::Url(source), PackageRefs::Url(pkg_ref)) => source
+                .download(pkg_ref, project, reqwest)
+                .map_err(Into::into),
+
+            (PackageSources::Custom(source), PackageRefs::Custom(pkg_ref)) => source
+                .download(pkg_ref, project, reqwest)
+                .map_err(Into::into),
+
             _ => Err(errors::DownloadError::Mismatch),
         }
     }
@@ -181,9 +261,30 @@ pub mod errors {
         #[error("error refreshing pesde package source")]
         GitBased(#[from] crate::source::git_index::errors::RefreshError),
 
+        /// A pesde package source failed to refresh
+        #[error("error refreshing pesde package source")]
+        Pesde(#[from] crate::source::pesde::errors::RefreshError),
+
+        /// A Wally package source failed to refresh
+        #[cfg(feature = "wally-compat")]
+        #[error("error refreshing wally package source")]
+        Wally(#[from] crate::source::wally::errors::RefreshError),
+
         /// A workspace package source failed to refresh
         #[error("error refreshing workspace package source")]
         Workspace(#[from] crate::source::workspace::errors::RefreshError),
+
+        /// A path package source failed to refresh
+        #[error("error refreshing path package source")]
+        Path(#[from] crate::source::path::errors::RefreshError),
+
+        /// A URL package source failed to refresh
+        #[error("error refreshing url package source")]
+        Url(#[from] crate::source::url::errors::RefreshError),
+
+        /// A custom package source failed to refresh
+        #[error("error refreshing custom package source")]
+        Custom(#[from] crate::source::custom::errors::RefreshError),
     }
 
     /// Errors that can occur when resolving a package
@@ -210,6 +311,18 @@ pub mod errors {
         /// A workspace package source failed to resolve
         #[error("error resolving workspace package")]
         Workspace(#[from] crate::source::workspace::errors::ResolveError),
+
+        /// A path package source failed to resolve
+        #[error("error resolving path package")]
+        Path(#[from] crate::source::path::errors::ResolveError),
+
+        /// A URL package source failed to resolve
+        #[error("error resolving url package")]
+        Url(#[from] crate::source::url::errors::ResolveError),
+
+        /// A custom package source failed to resolve
+        #[error("error resolving custom package")]
+        Custom(#[from] crate::source::custom::errors::ResolveError),
     }
 
     /// Errors that can occur when downloading a package
@@ -236,5 +349,17 @@ pub mod errors {
         /// A workspace package source failed to download
         #[error("error downloading workspace package")]
         Workspace(#[from] crate::source::workspace::errors::DownloadError),
+
+        /// A path package source failed to download
+        #[error("error downloading path package")]
+        Path(#[from] crate::source::path::errors::DownloadError),
+
+        /// A URL package source failed to download
+        #[error("error downloading url package")]
+        Url(#[from] crate::source::url::errors::DownloadError),
+
+        /// A custom package source failed to download
+        #[error("error downloading custom package")]
+        Custom(#[from] crate::source::custom::errors::DownloadError),
     }
 }
@@ -0,0 +1,224 @@
+use crate::{
+    manifest::{
+        target::{Target, TargetKind},
+        Manifest,
+    },
+    names::PackageNames,
+    source::{
+        fs::PackageFS, path::pkg_ref::PathPackageRef, specifiers::DependencySpecifiers,
+        traits::PackageSource, version_id::VersionId, ResolveResult,
+    },
+    Project, DEFAULT_INDEX_NAME, LOCKFILE_FILE_NAME, MANIFEST_FILE_NAME,
+};
+use relative_path::RelativePathBuf;
+use reqwest::blocking::Client;
+use std::collections::BTreeMap;
+
+/// The path package reference
+pub mod pkg_ref;
+/// The path dependency specifier
+pub mod specifier;
+
+/// The path package source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathPackageSource;
+
+impl PackageSource for PathPackageSource {
+    type Specifier = specifier::PathDependencySpecifier;
+    type Ref = PathPackageRef;
+    type RefreshError = errors::RefreshError;
+    type ResolveError = errors::ResolveError;
+    type DownloadError = errors::DownloadError;
+
+    fn refresh(
+        &self,
+        _project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
+        // no-op
+        Ok(())
+    }
+
+    fn resolve(
+        &self,
+        specifier: &Self::Specifier,
+        project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+        _package_target: TargetKind,
+    ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
+        let dir = specifier.path.to_path(project.package_dir());
+
+        let manifest = std::fs::read_to_string(dir.join(MANIFEST_FILE_NAME))
+            .map_err(|e| errors::ResolveError::ReadManifest(dir.clone(), e))?;
+        let manifest = toml::from_str::<Manifest>(&manifest)
+            .map_err(|e| errors::ResolveError::DeserManifest(dir.clone(), e))?;
+
+        // the workspace's packages all share the same root for path references
+        let root = project.workspace_dir().unwrap_or(project.package_dir());
+        let path = RelativePathBuf::from_path(project.package_dir().strip_prefix(root).unwrap())
+            .unwrap()
+            .join_normalized(&specifier.path);
+
+        let mut lockfile = None;
+
+        let dependencies = manifest
+            .all_dependencies()?
+            .into_iter()
+            .map(|(alias, (mut spec, ty))| {
+                match &mut spec {
+                    DependencySpecifiers::Pesde(spec) => {
+                        let index_name = spec.index.as_deref().unwrap_or(DEFAULT_INDEX_NAME);
+
+                        spec.index = Some(
+                            manifest
+                                .indices
+                                .get(index_name)
+                                .ok_or(errors::ResolveError::IndexNotFound(
+                                    index_name.to_string(),
+                                    manifest.name.to_string(),
+                                ))?
+                                .to_string(),
+                        )
+                    }
+                    #[cfg(feature = "wally-compat")]
+                    DependencySpecifiers::Wally(spec) => {
+                        let index_name = spec.index.as_deref().unwrap_or(DEFAULT_INDEX_NAME);
+
+                        spec.index = Some(
+                            manifest
+                                .wally_indices
+                                .get(index_name)
+                                .ok_or(errors::ResolveError::IndexNotFound(
+                                    index_name.to_string(),
+                                    manifest.name.to_string(),
+                                ))?
+                                .to_string(),
+                        )
+                    }
+                    DependencySpecifiers::Git(_) => {}
+                    DependencySpecifiers::Url(_) => {}
+                    DependencySpecifiers::Custom(_) => {}
+                    DependencySpecifiers::Workspace(workspace_spec) => {
+                        let lockfile: &crate::Lockfile = match &lockfile {
+                            Some(lockfile) => lockfile,
+                            None => lockfile.insert(
+                                std::fs::read_to_string(dir.join(LOCKFILE_FILE_NAME))
+                                    .map_err(Into::into)
+                                    .and_then(|l| l.parse())
+                                    .map_err(|e| {
+                                        errors::ResolveError::ReadLockfile(dir.clone(), Box::new(e))
+                                    })?,
+                            ),
+                        };
+
+                        let target = workspace_spec.target.unwrap_or(manifest.target.kind());
+
+                        let member_path = lockfile
+                            .workspace
+                            .get(&workspace_spec.name)
+                            .and_then(|targets| targets.get(&target))
+                            .ok_or_else(|| {
+                                errors::ResolveError::NoPathForWorkspaceMember(
+                                    workspace_spec.name.to_string(),
+                                    target,
+                                    dir.clone(),
+                                )
+                            })?;
+
+                        spec = DependencySpecifiers::Path(specifier::PathDependencySpecifier {
+                            path: specifier.path.join_normalized(member_path),
+                        })
+                    }
+                    // the dependency's path is relative to the path package, but it will be
+                    // resolved relative to the project, so it has to be rebased
+                    DependencySpecifiers::Path(path_spec) => {
+                        path_spec.path = specifier.path.join_normalized(&path_spec.path);
+                    }
+                }
+
+                Ok((alias, (spec, ty)))
+            })
+            .collect::<Result<_, errors::ResolveError>>()?;
+
+        Ok((
+            PackageNames::Pesde(manifest.name.clone()),
+            BTreeMap::from([(
+                VersionId::new(manifest.version.clone(), manifest.target.kind()),
+                PathPackageRef {
+                    path,
+                    dependencies,
+                    target: manifest.target,
+                },
+            )]),
+        ))
+    }
+
+    fn download(
+        &self,
+        pkg_ref: &mut Self::Ref,
+        project: &Project,
+        _reqwest: &Client,
+    ) -> Result<(PackageFS, Target), Self::DownloadError> {
+        let root = project.workspace_dir().unwrap_or(project.package_dir());
+        let path = pkg_ref.path.to_path(root);
+
+        if !path.join(MANIFEST_FILE_NAME).is_file() {
+            return Err(errors::DownloadError::NotFound(path));
+        }
+
+        Ok((
+            PackageFS::Copy(path, pkg_ref.target.kind()),
+            pkg_ref.target.clone(),
+        ))
+    }
+}
+
+/// Errors that can occur when using a path package source
+pub mod errors {
+    use crate::manifest::target::TargetKind;
+    use std::path::PathBuf;
+    use thiserror::Error;
+
+    /// Errors that can occur when refreshing the path package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RefreshError {}
+
+    /// Errors that can occur when resolving a path package
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum ResolveError {
+        /// An error occurred reading the manifest of the package
+        #[error("failed to read manifest at {0}")]
+        ReadManifest(PathBuf, #[source] std::io::Error),
+
+        /// An error occurred deserializing the manifest of the package
+        #[error("failed to deserialize manifest at {0}")]
+        DeserManifest(PathBuf, #[source] toml::de::Error),
+
+        /// An error occurred getting all dependencies
+        #[error("failed to get all dependencies")]
+        AllDependencies(#[from] crate::manifest::errors::AllDependenciesError),
+
+        /// An index of the package was not found
+        #[error("index {0} not found in package {1}")]
+        IndexNotFound(String, String),
+
+        /// An error occurred reading the lockfile of the package
+        #[error("failed to read lockfile at {0}")]
+        ReadLockfile(PathBuf, #[source] Box<crate::errors::LockfileReadError>),
+
+        /// No path for a workspace member was found in the lockfile
+        #[error("no path found for workspace member {0} {1} in lockfile at {2}")]
+        NoPathForWorkspaceMember(String, TargetKind, PathBuf),
+    }
+
+    /// Errors that can occur when downloading a path package
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum DownloadError {
+        /// No package was found at the path
+        #[error("no package found at {0}")]
+        NotFound(PathBuf),
+    }
+}
@@ -0,0 +1,21 @@
+use relative_path::RelativePathBuf;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::source::DependencySpecifier;
+
+/// The specifier for a path dependency
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, JsonSchema)]
+pub struct PathDependencySpecifier {
+    /// The path of the package, relative to the directory of the manifest it is specified in
+    #[schemars(with = "std::path::PathBuf")]
+    pub path: RelativePathBuf,
+}
+impl DependencySpecifier for PathDependencySpecifier {}
+
+impl Display for PathDependencySpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "path:{}", self.path)
+    }
+}
@@ -2,12 +2,16 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Debug,
     hash::Hash,
-    path::PathBuf,
+    io::Write,
+    path::{Path, PathBuf},
 };
 
 use gix::Url;
 use relative_path::RelativePathBuf;
-use reqwest::header::{ACCEPT, AUTHORIZATION};
+use reqwest::{
+    header::{ACCEPT, AUTHORIZATION, ETAG, IF_NONE_MATCH},
+    StatusCode,
+};
 use serde::{Deserialize, Serialize};
 
 use pkg_ref::PesdePackageRef;
@@ -19,8 +23,9 @@ use crate::{
         DependencyType,
     },
     names::{PackageName, PackageNames},
+    progress::ProgressEvent,
     source::{
-        fs::{store_reader_in_cas, FSEntry, PackageFS},
+        fs::{file_mode, store_reader_in_cas, FSEntry, PackageFS},
         git_index::GitBasedSource,
         DependencySpecifiers, PackageSource, ResolveResult, VersionId, IGNORED_DIRS, IGNORED_FILES,
     },
@@ -42,6 +47,17 @@ pub struct PesdePackageSource {
 /// The file containing scope information
 pub const SCOPE_INFO_FILE: &str = "scope.toml";
 
+/// The prefix of an index URL's scheme which selects the sparse protocol, e.g. `sparse+https://`
+pub const SPARSE_SCHEME_PREFIX: &str = "sparse+";
+
+/// A file of a sparse index cached locally, along with the ETag it was served with
+#[derive(Debug, Serialize, Deserialize)]
+struct SparseCacheEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    etag: Option<String>,
+    contents: String,
+}
+
 /// Information about a scope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScopeInfo {
@@ -69,10 +85,167 @@ impl PesdePackageSource {
         self.repo_url.to_bstring().to_vec()
     }
 
-    /// Reads the config file
+    /// Whether this index is accessed over the sparse protocol instead of being cloned
+    pub fn is_sparse(&self) -> bool {
+        self.repo_url
+            .scheme
+            .as_str()
+            .starts_with(SPARSE_SCHEME_PREFIX)
+    }
+
+    /// The directory of this index, if it is a local directory which isn't a git repository
+    pub fn local_dir(&self, project: &Project) -> Option<PathBuf> {
+        let url = project.mirror_config.rewrite_url(&self.repo_url);
+        if url.scheme != gix::url::Scheme::File {
+            return None;
+        }
+
+        let path = gix::path::from_bstring(url.path);
+        let is_repo = gix::discover::is_git(&path).is_ok()
+            || gix::discover::is_git(&path.join(".git")).is_ok();
+
+        (path.is_dir() && !is_repo).then_some(path)
+    }
+
+    /// Reads a file from the index, using the sparse protocol if the index URL selects it, or
+    /// the filesystem if the index is a local directory
+    pub fn read_index_file(
+        &self,
+        file_path: &[&str],
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<Option<String>, errors::ReadIndexFileError> {
+        if self.is_sparse() {
+            return self.read_sparse_file(file_path, project, reqwest);
+        }
+
+        self.read_local_index_file(file_path, project)
+    }
+
+    /// Reads a file from the local copy of the index without making any requests. Files of sparse indices are read
+    /// from the copies cached when they were last fetched
+    pub fn read_local_index_file(
+        &self,
+        file_path: &[&str],
+        project: &Project,
+    ) -> Result<Option<String>, errors::ReadIndexFileError> {
+        if self.is_sparse() {
+            return Ok(self
+                .read_sparse_cache(&self.sparse_cache_path(file_path, project))?
+                .map(|cached| cached.contents));
+        }
+
+        if let Some(dir) = self.local_dir(project) {
+            let path = file_path.iter().fold(dir, |path, part| path.join(part));
+
+            return match std::fs::read_to_string(&path) {
+                Ok(s) => Ok(Some(s)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(errors::ReadIndexFileError::ReadLocal(path, e)),
+            };
+        }
+
+        self.read_file(file_path.iter().copied(), project, None)
+            .map_err(|e| Box::new(e).into())
+    }
+
+    /// The path a file of a sparse index is cached at
+    fn sparse_cache_path(&self, file_path: &[&str], project: &Project) -> PathBuf {
+        file_path
+            .iter()
+            .fold(self.path(project), |path, part| path.join(part))
+    }
+
+    fn read_sparse_cache(
+        &self,
+        cache_path: &Path,
+    ) -> Result<Option<SparseCacheEntry>, errors::ReadIndexFileError> {
+        match std::fs::read_to_string(cache_path) {
+            Ok(s) => Ok(Some(toml::from_str(&s)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(errors::ReadIndexFileError::ReadCache(e)),
+        }
+    }
+
+    /// Fetches a file from a sparse index, reusing the cached copy if the index reports it as unmodified
+    fn read_sparse_file(
+        &self,
+        file_path: &[&str],
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<Option<String>, errors::ReadIndexFileError> {
+        let repo_url = project
+            .mirror_config
+            .rewrite_url(&self.repo_url)
+            .to_bstring()
+            .to_string();
+        let base_url = repo_url
+            .strip_prefix(SPARSE_SCHEME_PREFIX)
+            .unwrap_or(&repo_url)
+            .trim_end_matches('/');
+        let url = url::Url::parse(&format!("{base_url}/{}", file_path.join("/")))
+            .map_err(|e| errors::ReadIndexFileError::Url(base_url.to_string(), e))?;
+
+        let cache_path = self.sparse_cache_path(file_path, project);
+        let cached = self.read_sparse_cache(&cache_path)?;
+
+        let mut request = reqwest.get(url.clone());
+
+        if let Some(token) = project.auth_config.tokens().get(&self.repo_url) {
+            log::debug!("using token for {}", self.repo_url);
+            request = request.header(AUTHORIZATION, token);
+        }
+
+        if let Some(etag) = cached.as_ref().and_then(|cached| cached.etag.as_deref()) {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+
+        let response = request.send()?;
+
+        match (response.status(), cached) {
+            (StatusCode::NOT_MODIFIED, Some(cached)) => {
+                log::debug!("using cached sparse index file {url}");
+                return Ok(Some(cached.contents));
+            }
+            (StatusCode::NOT_FOUND, _) => {
+                match std::fs::remove_file(&cache_path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(errors::ReadIndexFileError::WriteCache(e)),
+                }
+
+                return Ok(None);
+            }
+            _ => {}
+        }
+
+        let response = response.error_for_status()?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string());
+        let contents = response.text()?;
+
+        let entry = SparseCacheEntry { etag, contents };
+
+        // other threads may be reading the index at the same time, so the file is replaced atomically
+        let parent = cache_path.parent().unwrap();
+        std::fs::create_dir_all(parent).map_err(errors::ReadIndexFileError::WriteCache)?;
+        let mut file = tempfile::NamedTempFile::new_in(parent)
+            .map_err(errors::ReadIndexFileError::WriteCache)?;
+        file.write_all(toml::to_string(&entry)?.as_bytes())
+            .map_err(errors::ReadIndexFileError::WriteCache)?;
+        file.persist(&cache_path)
+            .map_err(|e| errors::ReadIndexFileError::WriteCache(e.error))?;
+
+        Ok(Some(entry.contents))
+    }
+
+    /// Reads the config file from the local copy of the index, which is brought up to date by refreshing the source
     pub fn config(&self, project: &Project) -> Result<IndexConfig, errors::ConfigError> {
         let file = self
-            .read_file(["config.toml"], project, None)
+            .read_local_index_file(&["config.toml"], project)
             .map_err(Box::new)?;
 
         let string = match file {
@@ -87,11 +260,17 @@ impl PesdePackageSource {
         toml::from_str(&string).map_err(Into::into)
     }
 
-    /// Reads all packages from the index
+    /// Reads all packages from the index. Sparse indices can't list their packages, so they aren't supported
     pub fn all_packages(
         &self,
         project: &Project,
     ) -> Result<BTreeMap<PackageName, IndexFile>, errors::AllPackagesError> {
+        if self.is_sparse() {
+            return Err(errors::AllPackagesError::Sparse(Box::new(
+                self.repo_url.clone(),
+            )));
+        }
+
         let path = self.path(project);
 
         let repo = match gix::open(&path) {
@@ -182,22 +361,42 @@ impl PesdePackageSource {
 impl PackageSource for PesdePackageSource {
     type Specifier = PesdeDependencySpecifier;
     type Ref = PesdePackageRef;
-    type RefreshError = crate::source::git_index::errors::RefreshError;
+    type RefreshError = errors::RefreshError;
     type ResolveError = errors::ResolveError;
     type DownloadError = errors::DownloadError;
 
-    fn refresh(&self, project: &Project) -> Result<(), Self::RefreshError> {
-        GitBasedSource::refresh(self, project)
+    fn refresh(
+        &self,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
+        // other files of sparse indices are fetched as they are needed, but the config is read for every download,
+        // so it's only brought up to date here
+        if self.is_sparse() {
+            self.read_sparse_file(&["config.toml"], project, reqwest)
+                .map_err(Box::new)?;
+            return Ok(());
+        }
+
+        // files of local directory indices are read as they are needed
+        if self.local_dir(project).is_some() {
+            return Ok(());
+        }
+
+        GitBasedSource::refresh(self, project).map_err(Box::new)?;
+
+        Ok(())
     }
 
     fn resolve(
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        reqwest: &reqwest::blocking::Client,
         package_target: TargetKind,
     ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
         let (scope, name) = specifier.name.as_str();
-        let string = match self.read_file([scope, name], project, None) {
+        let string = match self.read_index_file(&[scope, name], project, reqwest) {
             Ok(Some(s)) => s,
             Ok(None) => return Err(Self::ResolveError::NotFound(specifier.name.to_string())),
             Err(e) => {
@@ -232,6 +431,7 @@ impl PackageSource for PesdePackageSource {
                             index_url: self.repo_url.clone(),
                             dependencies: entry.dependencies,
                             target: entry.target,
+                            archive_hash: entry.archive_hash,
                         },
                     )
                 })
@@ -241,17 +441,13 @@ impl PackageSource for PesdePackageSource {
 
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         reqwest: &reqwest::blocking::Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError> {
         let config = self.config(project).map_err(Box::new)?;
-        let index_file = project
-            .cas_dir
-            .join("index")
-            .join(pkg_ref.name.escaped())
-            .join(pkg_ref.version.to_string())
-            .join(pkg_ref.target.to_string());
+        let index_file = pkg_ref.index_file(project);
+        let _lock = project.lock_path(&index_file)?;
 
         match std::fs::read_to_string(&index_file) {
             Ok(s) => {
@@ -267,23 +463,65 @@ impl PackageSource for PesdePackageSource {
             Err(e) => return Err(errors::DownloadError::ReadIndex(e)),
         }
 
-        let url = config
-            .download()
+        let download = config.download();
+        let url = project
+            .mirror_config
+            .rewrite(&download)
+            .unwrap_or(download)
             .replace("{PACKAGE}", &pkg_ref.name.to_string().replace("/", "%2F"))
             .replace("{PACKAGE_VERSION}", &pkg_ref.version.to_string())
             .replace("{PACKAGE_TARGET}", &pkg_ref.target.to_string());
 
-        let mut request = reqwest.get(&url).header(ACCEPT, "application/octet-stream");
+        let bytes = match url::Url::parse(&url)
+            .ok()
+            .filter(|url| url.scheme() == "file")
+            .and_then(|url| url.to_file_path().ok())
+        {
+            // archives of local indices may sit on the filesystem beside them
+            Some(path) => {
+                std::fs::read(&path).map_err(|e| errors::DownloadError::ReadArchive(path, e))?
+            }
+            None => {
+                let token = project.auth_config.tokens().get(&self.repo_url);
+                if token.is_some() {
+                    log::debug!("using token for {}", self.repo_url);
+                }
 
-        if let Some(token) = project.auth_config.tokens().get(&self.repo_url) {
-            log::debug!("using token for {}", self.repo_url);
-            request = request.header(AUTHORIZATION, token);
-        }
+                project.retry_config.download(
+                    &url,
+                    || {
+                        let request = reqwest.get(&url).header(ACCEPT, "application/octet-stream");
+
+                        match token {
+                            Some(token) => request.header(AUTHORIZATION, token),
+                            None => request,
+                        }
+                    },
+                    |downloaded, total| {
+                        project.report(ProgressEvent::DownloadProgress {
+                            package: None,
+                            url: url.clone(),
+                            downloaded,
+                            total,
+                        })
+                    },
+                )?
+            }
+        };
+
+        if let Some(expected) = &pkg_ref.archive_hash {
+            let actual = hash(&bytes);
 
-        let response = request.send()?.error_for_status()?;
-        let bytes = response.bytes()?;
+            if &actual != expected {
+                return Err(errors::DownloadError::HashMismatch(
+                    format!("{}@{} {}", pkg_ref.name, pkg_ref.version, pkg_ref.target),
+                    expected.clone(),
+                    actual,
+                ));
+            }
+        }
 
-        let mut decoder = flate2::read::GzDecoder::new(bytes.as_ref());
+        let mut decoder = flate2::read::GzDecoder::new(bytes.as_slice());
         let mut archive = tar::Archive::new(&mut decoder);
 
         let mut entries = BTreeMap::new();
@@ -310,8 +548,9 @@ impl PackageSource for PesdePackageSource {
                 continue;
             }
 
+            let mode = file_mode(entry.header().mode()?);
             let hash = store_reader_in_cas(project.cas_dir(), &mut entry)?;
-            entries.insert(path, FSEntry::File(hash));
+            entries.insert(path, FSEntry::File(hash, mode));
         }
 
         let fs = PackageFS::CAS(entries);
@@ -453,11 +692,157 @@ pub struct IndexFileEntry {
     /// The dependencies of this package
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
+
+    /// The SHA-256 hash of this package's archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_hash: Option<String>,
 }
 
 /// The index file for a package
 pub type IndexFile = BTreeMap<VersionId, IndexFileEntry>;
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthConfig;
+    use std::path::Path;
+
+    /// Writes a directory index with a single package, returning its URL and the hash of the package's archive
+    fn write_index(root: &Path) -> (Url, String) {
+        let index = root.join("index");
+        std::fs::create_dir_all(index.join("acme")).unwrap();
+        std::fs::write(
+            index.join("config.toml"),
+            format!(
+                "api = \"http://127.0.0.1:1\"\ndownload = \"file://{}/{{PACKAGE}}/{{PACKAGE_VERSION}}/{{PACKAGE_TARGET}}.tar.gz\"\n",
+                root.join("archives").display()
+            ),
+        )
+        .unwrap();
+
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+            vec![],
+            flate2::Compression::default(),
+        ));
+        for (path, contents) in [
+            ("pesde.toml", "name = \"acme/foo\"\nversion = \"0.1.0\"\n"),
+            ("init.luau", "return {}"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            archive
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        let archive = archive.into_inner().unwrap().finish().unwrap();
+        let archive_hash = hash(&archive);
+
+        let archive_path = root.join("archives").join("acme/foo/0.1.0/luau.tar.gz");
+        std::fs::create_dir_all(archive_path.parent().unwrap()).unwrap();
+        std::fs::write(archive_path, archive).unwrap();
+
+        std::fs::write(
+            index.join("acme").join("foo"),
+            format!(
+                "[\"0.1.0 luau\"]\narchive_hash = \"{archive_hash}\"\n\n[\"0.1.0 luau\".target]\nenvironment = \"luau\"\nlib = \"init.luau\"\n"
+            ),
+        )
+        .unwrap();
+
+        let url = format!("file://{}", index.display())
+            .as_str()
+            .try_into()
+            .unwrap();
+
+        (url, archive_hash)
+    }
+
+    fn project(root: &Path) -> Project {
+        Project::new(
+            root.join("project"),
+            None::<PathBuf>,
+            root.join("data"),
+            root.join("cas"),
+            AuthConfig::default(),
+        )
+    }
+
+    fn specifier() -> PesdeDependencySpecifier {
+        PesdeDependencySpecifier {
+            name: "acme/foo".parse().unwrap(),
+            version: "^0.1.0".parse().unwrap(),
+            index: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn resolves_and_downloads_from_directory_indices() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, archive_hash) = write_index(dir.path());
+        let project = project(dir.path());
+        let reqwest = reqwest::blocking::Client::new();
+
+        let source = PesdePackageSource::new(url);
+        assert_eq!(source.local_dir(&project), Some(dir.path().join("index")));
+        PackageSource::refresh(&source, &project, &reqwest).unwrap();
+        assert!(
+            !source.path(&project).exists(),
+            "directory indices aren't cloned"
+        );
+
+        let (_, mut versions) = source
+            .resolve(&specifier(), &project, &reqwest, TargetKind::Luau)
+            .unwrap();
+        let mut pkg_ref = versions
+            .remove(&VersionId::new("0.1.0".parse().unwrap(), TargetKind::Luau))
+            .unwrap();
+        assert_eq!(pkg_ref.archive_hash.as_deref(), Some(archive_hash.as_str()));
+
+        let (PackageFS::CAS(entries), _) =
+            source.download(&mut pkg_ref, &project, &reqwest).unwrap()
+        else {
+            panic!("expected a CAS file system");
+        };
+        assert!(matches!(
+            entries.get(&RelativePathBuf::from("init.luau")),
+            Some(FSEntry::File(..))
+        ));
+    }
+
+    #[test]
+    fn rejects_archives_not_matching_their_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = write_index(dir.path());
+        let project = project(dir.path());
+        let reqwest = reqwest::blocking::Client::new();
+
+        let source = PesdePackageSource::new(url);
+        let (_, versions) = source
+            .resolve(&specifier(), &project, &reqwest, TargetKind::Luau)
+            .unwrap();
+        let mut pkg_ref = versions.into_values().next().unwrap();
+        pkg_ref.archive_hash = Some(hash("something else"));
+
+        assert!(matches!(
+            source.download(&mut pkg_ref, &project, &reqwest),
+            Err(errors::DownloadError::HashMismatch(..))
+        ));
+    }
+
+    #[test]
+    fn git_repositories_are_not_directory_indices() {
+        let dir = tempfile::tempdir().unwrap();
+        let (url, _) = write_index(dir.path());
+        gix::init(dir.path().join("index")).unwrap();
+
+        let source = PesdePackageSource::new(url);
+        assert_eq!(source.local_dir(&project(dir.path())), None);
+    }
+}
+
 /// Errors that can occur when interacting with the pesde package source
 pub mod errors {
     use std::path::PathBuf;
@@ -466,6 +851,19 @@ pub mod errors {
 
     use crate::source::git_index::errors::{ReadFile, TreeError};
 
+    /// Errors that can occur when refreshing a pesde package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RefreshError {
+        /// Error refreshing the index repository
+        #[error("error refreshing index")]
+        Refresh(#[from] Box<crate::source::git_index::errors::RefreshError>),
+
+        /// Error fetching the config of a sparse index
+        #[error("error fetching config of sparse index")]
+        Config(#[from] Box<ReadIndexFileError>),
+    }
+
     /// Errors that can occur when resolving a package from a pesde package source
     #[derive(Debug, Error)]
     #[non_exhaustive]
@@ -480,7 +878,7 @@ pub mod errors {
 
         /// Error reading file for package
         #[error("error reading file for {0}")]
-        Read(String, #[source] Box<ReadFile>),
+        Read(String, #[source] Box<ReadIndexFileError>),
 
         /// Error parsing file for package
         #[error("error parsing file for {0}")]
@@ -491,13 +889,50 @@ pub mod errors {
         Utf8(String, #[source] std::string::FromUtf8Error),
     }
 
+    /// Errors that can occur when reading a file from a pesde package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum ReadIndexFileError {
+        /// Error reading file from the index repository
+        #[error("error reading file from index repository")]
+        ReadFile(#[from] Box<ReadFile>),
+
+        /// The URL of a sparse index file is invalid
+        #[error("invalid sparse index url {0}")]
+        Url(String, #[source] url::ParseError),
+
+        /// Error requesting file from sparse index
+        #[error("error requesting file from sparse index")]
+        Request(#[from] reqwest::Error),
+
+        /// Error reading cached sparse index file
+        #[error("error reading cached sparse index file")]
+        ReadCache(#[source] std::io::Error),
+
+        /// Error deserializing cached sparse index file
+        #[error("error deserializing cached sparse index file")]
+        DeserializeCache(#[from] toml::de::Error),
+
+        /// Error serializing cached sparse index file
+        #[error("error serializing cached sparse index file")]
+        SerializeCache(#[from] toml::ser::Error),
+
+        /// Error writing cached sparse index file
+        #[error("error writing cached sparse index file")]
+        WriteCache(#[source] std::io::Error),
+
+        /// Error reading file from local directory index
+        #[error("error reading index file at {0}")]
+        ReadLocal(PathBuf, #[source] std::io::Error),
+    }
+
     /// Errors that can occur when reading the config file for a pesde package source
     #[derive(Debug, Error)]
     #[non_exhaustive]
     pub enum ConfigError {
         /// Error reading file
         #[error("error reading config file")]
-        ReadFile(#[from] Box<ReadFile>),
+        ReadFile(#[from] Box<ReadIndexFileError>),
 
         /// Error parsing config file
         #[error("error parsing config file")]
@@ -512,6 +947,10 @@ pub mod errors {
     #[derive(Debug, Error)]
     #[non_exhaustive]
     pub enum AllPackagesError {
+        /// The index is sparse, so its packages can't be listed
+        #[error("cannot list the packages of sparse index {0}")]
+        Sparse(Box<gix::Url>),
+
         /// Error opening the repository
         #[error("error opening repository at {0}")]
         Open(PathBuf, #[source] Box<gix::open::Error>),
@@ -547,7 +986,7 @@ pub mod errors {
 
         /// Error downloading package
         #[error("error downloading package")]
-        Download(#[from] reqwest::Error),
+        Download(#[from] crate::retry::errors::RetryError),
 
         /// Error unpacking package
         #[error("error unpacking package")]
@@ -568,5 +1007,13 @@ pub mod errors {
         /// Error writing index file
         #[error("error reading index file")]
         ReadIndex(#[source] std::io::Error),
+
+        /// Error reading archive from the filesystem
+        #[error("error reading archive at {0}")]
+        ReadArchive(PathBuf, #[source] std::io::Error),
+
+        /// The downloaded archive's hash doesn't match the one recorded for it
+        #[error("archive hash mismatch for {0}, expected {1} but got {2}")]
+        HashMismatch(String, String, String),
     }
 }
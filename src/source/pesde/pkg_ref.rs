@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -10,6 +10,7 @@ use crate::{
     },
     names::PackageName,
     source::{pesde::PesdePackageSource, DependencySpecifiers, PackageRef, PackageSources},
+    Project,
 };
 
 /// A pesde package reference
@@ -30,7 +31,22 @@ pub struct PesdePackageRef {
     pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
     /// The target of the package
     pub target: Target,
+    /// The SHA-256 hash of the package's archive, if the index recorded one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_hash: Option<String>,
 }
+impl PesdePackageRef {
+    /// The path of the cached file system of this package
+    pub(crate) fn index_file(&self, project: &Project) -> PathBuf {
+        project
+            .cas_dir
+            .join("index")
+            .join(self.name.escaped())
+            .join(self.version.to_string())
+            .join(self.target.to_string())
+    }
+}
+
 impl PackageRef for PesdePackageRef {
     fn dependencies(&self) -> &BTreeMap<String, (DependencySpecifiers, DependencyType)> {
         &self.dependencies
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 /// All possible package references
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "snake_case", tag = "ref_ty")]
 pub enum PackageRefs {
     /// A pesde package reference
@@ -18,6 +18,12 @@ pub enum PackageRefs {
     Git(crate::source::git::pkg_ref::GitPackageRef),
     /// A workspace package reference
     Workspace(crate::source::workspace::pkg_ref::WorkspacePackageRef),
+    /// A path package reference
+    Path(crate::source::path::pkg_ref::PathPackageRef),
+    /// A URL package reference
+    Url(crate::source::url::pkg_ref::UrlPackageRef),
+    /// A custom package reference
+    Custom(crate::source::custom::pkg_ref::CustomPackageRef),
 }
 
 impl PackageRefs {
@@ -40,6 +46,9 @@ impl PackageRef for PackageRefs {
             PackageRefs::Wally(pkg_ref) => pkg_ref.dependencies(),
             PackageRefs::Git(pkg_ref) => pkg_ref.dependencies(),
             PackageRefs::Workspace(pkg_ref) => pkg_ref.dependencies(),
+            PackageRefs::Path(pkg_ref) => pkg_ref.dependencies(),
+            PackageRefs::Url(pkg_ref) => pkg_ref.dependencies(),
+            PackageRefs::Custom(pkg_ref) => pkg_ref.dependencies(),
         }
     }
 
@@ -50,6 +59,9 @@ impl PackageRef for PackageRefs {
             PackageRefs::Wally(pkg_ref) => pkg_ref.use_new_structure(),
             PackageRefs::Git(pkg_ref) => pkg_ref.use_new_structure(),
             PackageRefs::Workspace(pkg_ref) => pkg_ref.use_new_structure(),
+            PackageRefs::Path(pkg_ref) => pkg_ref.use_new_structure(),
+            PackageRefs::Url(pkg_ref) => pkg_ref.use_new_structure(),
+            PackageRefs::Custom(pkg_ref) => pkg_ref.use_new_structure(),
         }
     }
 
@@ -60,6 +72,9 @@ impl PackageRef for PackageRefs {
             PackageRefs::Wally(pkg_ref) => pkg_ref.target_kind(),
             PackageRefs::Git(pkg_ref) => pkg_ref.target_kind(),
             PackageRefs::Workspace(pkg_ref) => pkg_ref.target_kind(),
+            PackageRefs::Path(pkg_ref) => pkg_ref.target_kind(),
+            PackageRefs::Url(pkg_ref) => pkg_ref.target_kind(),
+            PackageRefs::Custom(pkg_ref) => pkg_ref.target_kind(),
         }
     }
 
@@ -70,6 +85,9 @@ impl PackageRef for PackageRefs {
             PackageRefs::Wally(pkg_ref) => pkg_ref.source(),
             PackageRefs::Git(pkg_ref) => pkg_ref.source(),
             PackageRefs::Workspace(pkg_ref) => pkg_ref.source(),
+            PackageRefs::Path(pkg_ref) => pkg_ref.source(),
+            PackageRefs::Url(pkg_ref) => pkg_ref.source(),
+            PackageRefs::Custom(pkg_ref) => pkg_ref.source(),
         }
     }
 }
@@ -7,6 +7,9 @@ use std::fmt::Display;
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, JsonSchema)]
 #[serde(untagged)]
 pub enum DependencySpecifiers {
+    // first, as the other specifiers would also accept its fields
+    /// A custom dependency specifier
+    Custom(crate::source::custom::specifier::CustomDependencySpecifier),
     /// A pesde dependency specifier
     Pesde(pesde::specifier::PesdeDependencySpecifier),
     /// A Wally dependency specifier
@@ -16,6 +19,10 @@ pub enum DependencySpecifiers {
     Git(crate::source::git::specifier::GitDependencySpecifier),
     /// A workspace dependency specifier
     Workspace(crate::source::workspace::specifier::WorkspaceDependencySpecifier),
+    /// A path dependency specifier
+    Path(crate::source::path::specifier::PathDependencySpecifier),
+    /// A URL dependency specifier
+    Url(crate::source::url::specifier::UrlDependencySpecifier),
 }
 impl DependencySpecifier for DependencySpecifiers {}
 
@@ -27,6 +34,69 @@ impl Display for DependencySpecifiers {
             DependencySpecifiers::Wally(specifier) => write!(f, "{specifier}"),
             DependencySpecifiers::Git(specifier) => write!(f, "{specifier}"),
             DependencySpecifiers::Workspace(specifier) => write!(f, "{specifier}"),
+            DependencySpecifiers::Path(specifier) => write!(f, "{specifier}"),
+            DependencySpecifiers::Url(specifier) => write!(f, "{specifier}"),
+            DependencySpecifiers::Custom(specifier) => write!(f, "{specifier}"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(specifier: &str) -> DependencySpecifiers {
+        toml::from_str(specifier).unwrap()
+    }
+
+    #[test]
+    fn parses_specifiers_without_a_custom_field_as_their_own_kind() {
+        assert!(matches!(
+            parse("name = \"acme/foo\"\nversion = \"^1.0.0\""),
+            DependencySpecifiers::Pesde(_)
+        ));
+        #[cfg(feature = "wally-compat")]
+        assert!(matches!(
+            parse("wally = \"acme/foo\"\nversion = \"^1.0.0\""),
+            DependencySpecifiers::Wally(_)
+        ));
+        assert!(matches!(
+            parse("repo = \"acme/foo\"\nrev = \"main\""),
+            DependencySpecifiers::Git(_)
+        ));
+        assert!(matches!(
+            parse("workspace = \"acme/foo\""),
+            DependencySpecifiers::Workspace(_)
+        ));
+        assert!(matches!(
+            parse("path = \"../foo\""),
+            DependencySpecifiers::Path(_)
+        ));
+        assert!(matches!(
+            parse("url = \"https://example.com/foo.tar.gz\""),
+            DependencySpecifiers::Url(_)
+        ));
+    }
+
+    #[test]
+    fn parses_specifiers_with_a_custom_field_as_custom() {
+        // fields another kind of specifier would accept are left to the custom source
+        let specifier = parse(
+            "custom = \"test\"\nname = \"acme/foo\"\nversion = \"^1.0.0\"\nrevision = 3\noptional = true",
+        );
+        let DependencySpecifiers::Custom(custom) = &specifier else {
+            panic!("expected a custom specifier, got {specifier:?}");
+        };
+
+        assert_eq!(custom.custom, "test");
+        assert_eq!(
+            custom.data["name"],
+            toml::Value::String("acme/foo".to_string())
+        );
+        assert_eq!(custom.data["revision"], toml::Value::Integer(3));
+        assert_eq!(custom.data["optional"], toml::Value::Boolean(true));
+        assert!(!custom.data.contains_key("custom"));
+
+        assert_eq!(parse(&toml::to_string(&specifier).unwrap()), specifier);
+    }
+}
@@ -41,7 +41,11 @@ pub trait PackageSource: Debug {
     type DownloadError: std::error::Error;
 
     /// Refreshes the source
-    fn refresh(&self, _project: &Project) -> Result<(), Self::RefreshError> {
+    fn refresh(
+        &self,
+        _project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
         Ok(())
     }
 
@@ -50,13 +54,14 @@ pub trait PackageSource: Debug {
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        reqwest: &reqwest::blocking::Client,
         project_target: TargetKind,
     ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError>;
 
-    /// Downloads a package
+    /// Downloads a package, recording any information only known after downloading (such as the archive's hash) in the reference
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         reqwest: &reqwest::blocking::Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError>;
@@ -0,0 +1,407 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use relative_path::RelativePathBuf;
+use reqwest::header::ACCEPT;
+
+use pkg_ref::UrlPackageRef;
+use specifier::UrlDependencySpecifier;
+
+use crate::{
+    manifest::{
+        target::{Target, TargetKind},
+        Manifest,
+    },
+    names::PackageNames,
+    progress::ProgressEvent,
+    source::{
+        fs::{file_mode, store_reader_in_cas, FSEntry, PackageFS},
+        DependencySpecifiers, PackageSource, ResolveResult, VersionId, IGNORED_DIRS, IGNORED_FILES,
+    },
+    util::hash,
+    Project, DEFAULT_INDEX_NAME, MANIFEST_FILE_NAME,
+};
+
+/// The URL package reference
+pub mod pkg_ref;
+/// The URL dependency specifier
+pub mod specifier;
+
+/// The URL package source
+#[derive(Debug, Hash, PartialEq, Eq, Clone)]
+pub struct UrlPackageSource {
+    url: url::Url,
+}
+
+impl UrlPackageSource {
+    /// Creates a new URL package source
+    pub fn new(url: url::Url) -> Self {
+        Self { url }
+    }
+
+    /// The path of the cached file system of the archive with the given hash
+    fn cache_path(&self, project: &Project, archive_hash: &str) -> PathBuf {
+        project
+            .cas_dir
+            .join("url")
+            .join(hash(self.url.as_str()))
+            .join(archive_hash)
+    }
+
+    fn read_cached(
+        &self,
+        project: &Project,
+        archive_hash: &str,
+    ) -> Result<Option<BTreeMap<RelativePathBuf, FSEntry>>, errors::FetchError> {
+        match std::fs::read_to_string(self.cache_path(project, archive_hash)) {
+            Ok(s) => {
+                log::debug!("using cached file system for {}", self.url);
+                Ok(Some(toml::from_str(&s)?))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(errors::FetchError::ReadCache(e)),
+        }
+    }
+
+    /// Downloads the archive, stores its contents in the CAS, and caches the resulting file system
+    fn fetch(
+        &self,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+        expected_hash: Option<&str>,
+    ) -> Result<(BTreeMap<RelativePathBuf, FSEntry>, String), errors::FetchError> {
+        let lock = expected_hash
+            .map(|archive_hash| project.lock_path(self.cache_path(project, archive_hash)))
+            .transpose()?;
+
+        if let Some(entries) = expected_hash
+            .map(|archive_hash| self.read_cached(project, archive_hash))
+            .transpose()?
+            .flatten()
+        {
+            return Ok((entries, expected_hash.unwrap().to_string()));
+        }
+
+        let url = project
+            .mirror_config
+            .rewrite(self.url.as_str())
+            .unwrap_or_else(|| self.url.to_string());
+
+        let bytes = project.retry_config.download(
+            &url,
+            || reqwest.get(&url).header(ACCEPT, "application/octet-stream"),
+            |downloaded, total| {
+                project.report(ProgressEvent::DownloadProgress {
+                    package: None,
+                    url: url.clone(),
+                    downloaded,
+                    total,
+                })
+            },
+        )?;
+
+        let archive_hash = hash(&bytes);
+        if let Some(expected) = expected_hash {
+            if archive_hash != expected {
+                return Err(errors::FetchError::HashMismatch(
+                    Box::new(self.url.clone()),
+                    expected.to_string(),
+                    archive_hash,
+                ));
+            }
+        }
+
+        let mut decoder = flate2::read::GzDecoder::new(bytes.as_slice());
+        let mut archive = tar::Archive::new(&mut decoder);
+
+        let mut entries = BTreeMap::new();
+
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = archive_path(&entry.path()?)
+                .ok_or_else(|| errors::FetchError::InvalidPath(entry.path_bytes().to_vec()))?;
+            if path.as_str().is_empty() {
+                continue;
+            }
+
+            if entry.header().entry_type().is_dir() {
+                if path
+                    .components()
+                    .next()
+                    .is_some_and(|ct| IGNORED_DIRS.contains(&ct.as_str()))
+                {
+                    continue;
+                }
+
+                entries.insert(path, FSEntry::Directory);
+
+                continue;
+            }
+
+            if IGNORED_FILES.contains(&path.as_str()) {
+                continue;
+            }
+
+            let mode = file_mode(entry.header().mode()?);
+            let hash = store_reader_in_cas(project.cas_dir(), &mut entry)?;
+            entries.insert(path, FSEntry::File(hash, mode));
+        }
+
+        let cache_path = self.cache_path(project, &archive_hash);
+        let _lock = match lock {
+            Some(lock) => lock,
+            None => project.lock_path(&cache_path)?,
+        };
+
+        if let Some(parent) = cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&cache_path, toml::to_string(&entries)?)
+            .map_err(errors::FetchError::WriteCache)?;
+
+        Ok((entries, archive_hash))
+    }
+}
+
+/// Converts the path of an archive entry into one relative to the package's root, rejecting paths which would
+/// escape it
+fn archive_path(path: &Path) -> Option<RelativePathBuf> {
+    let mut relative = RelativePathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => relative.push(part.to_str()?),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(relative)
+}
+
+impl PackageSource for UrlPackageSource {
+    type Specifier = UrlDependencySpecifier;
+    type Ref = UrlPackageRef;
+    type RefreshError = errors::RefreshError;
+    type ResolveError = errors::ResolveError;
+    type DownloadError = errors::DownloadError;
+
+    fn resolve(
+        &self,
+        specifier: &Self::Specifier,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+        _project_target: TargetKind,
+    ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
+        let (entries, archive_hash) = self.fetch(project, reqwest, specifier.sha256.as_deref())?;
+
+        let manifest = match entries.get(&RelativePathBuf::from(MANIFEST_FILE_NAME)) {
+            Some(FSEntry::File(hash, _)) => {
+                let (prefix, rest) = hash.split_at(2);
+                std::fs::read_to_string(project.cas_dir().join(prefix).join(rest)).ok()
+            }
+            _ => None,
+        }
+        .ok_or_else(|| errors::ResolveError::NoManifest(Box::new(self.url.clone())))?;
+        let manifest = toml::from_str::<Manifest>(&manifest)
+            .map_err(|e| errors::ResolveError::DeserManifest(Box::new(self.url.clone()), e))?;
+
+        let dependencies = manifest
+            .all_dependencies()
+            .map_err(|e| errors::ResolveError::CollectDependencies(Box::new(self.url.clone()), e))?
+            .into_iter()
+            .map(|(alias, (mut spec, ty))| {
+                match &mut spec {
+                    DependencySpecifiers::Pesde(spec) => {
+                        let index_name = spec.index.as_deref().unwrap_or(DEFAULT_INDEX_NAME);
+
+                        spec.index = Some(
+                            manifest
+                                .indices
+                                .get(index_name)
+                                .ok_or_else(|| {
+                                    errors::ResolveError::IndexNotFound(
+                                        index_name.to_string(),
+                                        Box::new(self.url.clone()),
+                                    )
+                                })?
+                                .to_string(),
+                        )
+                    }
+                    #[cfg(feature = "wally-compat")]
+                    DependencySpecifiers::Wally(spec) => {
+                        let index_name = spec.index.as_deref().unwrap_or(DEFAULT_INDEX_NAME);
+
+                        spec.index = Some(
+                            manifest
+                                .wally_indices
+                                .get(index_name)
+                                .ok_or_else(|| {
+                                    errors::ResolveError::IndexNotFound(
+                                        index_name.to_string(),
+                                        Box::new(self.url.clone()),
+                                    )
+                                })?
+                                .to_string(),
+                        )
+                    }
+                    DependencySpecifiers::Git(_) => {}
+                    DependencySpecifiers::Url(_) => {}
+                    DependencySpecifiers::Custom(_) => {}
+                    // the archive's surroundings are unknown, so there is nothing to resolve these against
+                    DependencySpecifiers::Workspace(_) | DependencySpecifiers::Path(_) => {
+                        return Err(errors::ResolveError::LocalDependency(
+                            spec.to_string(),
+                            Box::new(self.url.clone()),
+                        ));
+                    }
+                }
+
+                Ok((alias, (spec, ty)))
+            })
+            .collect::<Result<_, errors::ResolveError>>()?;
+
+        Ok((
+            PackageNames::Pesde(manifest.name),
+            BTreeMap::from([(
+                VersionId::new(manifest.version, manifest.target.kind()),
+                UrlPackageRef {
+                    url: self.url.clone(),
+                    archive_hash,
+                    dependencies,
+                    target: manifest.target,
+                },
+            )]),
+        ))
+    }
+
+    fn download(
+        &self,
+        pkg_ref: &mut Self::Ref,
+        project: &Project,
+        reqwest: &reqwest::blocking::Client,
+    ) -> Result<(PackageFS, Target), Self::DownloadError> {
+        let (entries, _) = self.fetch(project, reqwest, Some(&pkg_ref.archive_hash))?;
+
+        Ok((PackageFS::CAS(entries), pkg_ref.target.clone()))
+    }
+}
+
+/// Errors that can occur when interacting with the URL package source
+pub mod errors {
+    use thiserror::Error;
+
+    /// Errors that can occur when refreshing the URL package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RefreshError {}
+
+    /// Errors that can occur when fetching a package's archive
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum FetchError {
+        /// An error occurred while downloading the archive
+        #[error("error downloading archive")]
+        Download(#[from] crate::retry::errors::RetryError),
+
+        /// The archive contains an entry whose path is absolute or leaves the package's root
+        #[error("archive entry has an invalid path {}", String::from_utf8_lossy(.0))]
+        InvalidPath(Vec<u8>),
+
+        /// The archive's hash doesn't match the expected one
+        #[error("archive hash mismatch for {0}, expected {1} but got {2}")]
+        HashMismatch(Box<url::Url>, String, String),
+
+        /// An error occurred interacting with the file system
+        #[error("error interacting with the file system")]
+        Io(#[from] std::io::Error),
+
+        /// An error occurred reading the cached file system
+        #[error("error reading cached file system")]
+        ReadCache(#[source] std::io::Error),
+
+        /// An error occurred deserializing the cached file system
+        #[error("error deserializing cached file system")]
+        DeserializeCache(#[from] toml::de::Error),
+
+        /// An error occurred serializing the file system
+        #[error("error serializing file system")]
+        SerializeCache(#[from] toml::ser::Error),
+
+        /// An error occurred writing the cached file system
+        #[error("error writing cached file system")]
+        WriteCache(#[source] std::io::Error),
+    }
+
+    /// Errors that can occur when resolving a package from the URL package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum ResolveError {
+        /// An error occurred fetching the archive
+        #[error("error fetching archive")]
+        Fetch(#[from] FetchError),
+
+        /// The archive doesn't contain a manifest
+        #[error("no manifest found in archive at {0}")]
+        NoManifest(Box<url::Url>),
+
+        /// An error occurred deserializing the manifest
+        #[error("error deserializing manifest in archive at {0}")]
+        DeserManifest(Box<url::Url>, #[source] toml::de::Error),
+
+        /// An error occurred collecting the dependencies of the package
+        #[error("error collecting dependencies for archive at {0}")]
+        CollectDependencies(
+            Box<url::Url>,
+            #[source] crate::manifest::errors::AllDependenciesError,
+        ),
+
+        /// An index of the package was not found in its manifest
+        #[error("index {0} not found in manifest of archive at {1}")]
+        IndexNotFound(String, Box<url::Url>),
+
+        /// The package depends on a local package, which can't be resolved from an archive
+        #[error("local dependency {0} of archive at {1} cannot be resolved")]
+        LocalDependency(String, Box<url::Url>),
+    }
+
+    /// Errors that can occur when downloading a package from the URL package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum DownloadError {
+        /// An error occurred fetching the archive
+        #[error("error fetching archive")]
+        Fetch(#[from] FetchError),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_paths_stay_inside_the_package() {
+        assert_eq!(
+            archive_path(Path::new("./src/init.luau")),
+            Some(RelativePathBuf::from("src/init.luau"))
+        );
+        assert_eq!(archive_path(Path::new("./")), Some(RelativePathBuf::new()));
+
+        assert_eq!(archive_path(Path::new("/etc/passwd")), None);
+        assert_eq!(archive_path(Path::new("../outside.luau")), None);
+        assert_eq!(archive_path(Path::new("src/../../outside.luau")), None);
+    }
+
+    #[test]
+    fn lowercases_expected_hashes() {
+        let specifier = toml::from_str::<UrlDependencySpecifier>(
+            "url = \"https://example.com/foo.tar.gz\"\nsha256 = \"ABCDEF\"",
+        )
+        .unwrap();
+
+        assert_eq!(specifier.sha256.as_deref(), Some("abcdef"));
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::{
+    manifest::{
+        target::{Target, TargetKind},
+        DependencyType,
+    },
+    source::{url::UrlPackageSource, DependencySpecifiers, PackageRef, PackageSources},
+    Project,
+};
+
+/// A URL package reference
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+pub struct UrlPackageRef {
+    /// The URL of the package's archive
+    pub url: url::Url,
+    /// The SHA-256 hash of the package's archive
+    pub archive_hash: String,
+    /// The dependencies of the package
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
+    /// The target of the package
+    pub target: Target,
+}
+impl UrlPackageRef {
+    /// The path of the cached file system of this package
+    pub(crate) fn index_file(&self, project: &Project) -> PathBuf {
+        UrlPackageSource::new(self.url.clone()).cache_path(project, &self.archive_hash)
+    }
+}
+
+impl PackageRef for UrlPackageRef {
+    fn dependencies(&self) -> &BTreeMap<String, (DependencySpecifiers, DependencyType)> {
+        &self.dependencies
+    }
+
+    fn use_new_structure(&self) -> bool {
+        true
+    }
+
+    fn target_kind(&self) -> TargetKind {
+        self.target.kind()
+    }
+
+    fn source(&self) -> PackageSources {
+        PackageSources::Url(UrlPackageSource::new(self.url.clone()))
+    }
+}
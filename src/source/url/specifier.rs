@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::source::DependencySpecifier;
+
+/// The specifier for a URL dependency
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, JsonSchema)]
+pub struct UrlDependencySpecifier {
+    /// The URL of the package's archive
+    pub url: url::Url,
+    /// The expected SHA-256 hash of the package's archive
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "crate::util::deserialize_lowercase_opt"
+    )]
+    pub sha256: Option<String>,
+}
+impl DependencySpecifier for UrlDependencySpecifier {}
+
+impl Display for UrlDependencySpecifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url)
+    }
+}
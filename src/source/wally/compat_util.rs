@@ -7,7 +7,7 @@ use tempfile::TempDir;
 use crate::{
     manifest::target::Target,
     scripts::{execute_script, ScriptName},
-    source::wally::manifest::{Realm, WallyManifest},
+    source::wally::manifest::{Realm, WallyManifest, WALLY_MANIFEST_FILE_NAME},
     Project, LINK_LIB_NO_FILE_FOUND,
 };
 
@@ -51,8 +51,6 @@ pub(crate) fn find_lib_path(
     }
 }
 
-pub(crate) const WALLY_MANIFEST_FILE_NAME: &str = "wally.toml";
-
 pub(crate) fn get_target(
     project: &Project,
     tempdir: &TempDir,
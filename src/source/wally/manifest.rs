@@ -9,24 +9,59 @@ use crate::{
     source::{specifiers::DependencySpecifiers, wally::specifier::WallyDependencySpecifier},
 };
 
+/// The name of the Wally manifest file
+pub const WALLY_MANIFEST_FILE_NAME: &str = "wally.toml";
+
+/// The realm of a Wally package
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "lowercase")]
 pub enum Realm {
+    /// The package can be used by both the client and the server
     #[serde(alias = "dev")]
     Shared,
+    /// The package can only be used by the server
     Server,
 }
 
+/// The package section of a Wally manifest
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct WallyPackage {
+    /// The name of the package
     pub name: WallyPackageName,
+    /// The version of the package
     pub version: Version,
+    /// The URL of the registry the package's dependencies are resolved from
     pub registry: url::Url,
+    /// The realm of the package
     pub realm: Realm,
+    /// The description of the package
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The license of the package
+    #[serde(default)]
+    pub license: Option<String>,
+    /// The authors of the package
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Whether the package is private
+    #[serde(default)]
+    pub private: bool,
+}
+
+/// Where a Wally project places its dependencies in the Roblox data model
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct WallyPlace {
+    /// The location of the shared dependencies
+    #[serde(default)]
+    pub shared_packages: Option<String>,
+    /// The location of the server dependencies
+    #[serde(default)]
+    pub server_packages: Option<String>,
 }
 
-pub fn deserialize_specifiers<'de, D: Deserializer<'de>>(
+pub(crate) fn deserialize_specifiers<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<BTreeMap<String, WallyDependencySpecifier>, D::Error> {
     // specifier is in form of `name@version_req`
@@ -49,14 +84,22 @@ pub fn deserialize_specifiers<'de, D: Deserializer<'de>>(
         .collect()
 }
 
+/// A Wally manifest
 #[derive(Deserialize, Clone, Debug)]
 #[serde(rename_all = "kebab-case")]
 pub struct WallyManifest {
+    /// The package section of the manifest
     pub package: WallyPackage,
+    /// Where the project places its dependencies
+    #[serde(default)]
+    pub place: WallyPlace,
+    /// The shared dependencies of the package
     #[serde(default, deserialize_with = "deserialize_specifiers")]
     pub dependencies: BTreeMap<String, WallyDependencySpecifier>,
+    /// The server dependencies of the package
     #[serde(default, deserialize_with = "deserialize_specifiers")]
     pub server_dependencies: BTreeMap<String, WallyDependencySpecifier>,
+    /// The dev dependencies of the package
     #[serde(default, deserialize_with = "deserialize_specifiers")]
     pub dev_dependencies: BTreeMap<String, WallyDependencySpecifier>,
 }
@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, HashSet, VecDeque},
     path::PathBuf,
 };
 
@@ -12,8 +12,9 @@ use tempfile::tempdir;
 use crate::{
     manifest::target::{Target, TargetKind},
     names::PackageNames,
+    progress::ProgressEvent,
     source::{
-        fs::{store_reader_in_cas, FSEntry, PackageFS},
+        fs::{store_reader_in_cas, FSEntry, LinkStrategy, PackageFS},
         git_index::GitBasedSource,
         traits::PackageSource,
         version_id::VersionId,
@@ -25,7 +26,8 @@ use crate::{
 };
 
 pub(crate) mod compat_util;
-pub(crate) mod manifest;
+/// The Wally manifest
+pub mod manifest;
 /// The Wally package reference
 pub mod pkg_ref;
 /// The Wally dependency specifier
@@ -77,37 +79,90 @@ impl WallyPackageSource {
 
         serde_json::from_str(&string).map_err(Into::into)
     }
+
+    /// The fallback registries of this index
+    fn fallbacks(&self, project: &Project) -> Result<Vec<WallyPackageSource>, errors::ConfigError> {
+        Ok(self
+            .config(project)?
+            .fallback_registries
+            .into_iter()
+            .map(WallyPackageSource::new)
+            .collect())
+    }
 }
 
 impl PackageSource for WallyPackageSource {
     type Specifier = specifier::WallyDependencySpecifier;
     type Ref = WallyPackageRef;
-    type RefreshError = crate::source::git_index::errors::RefreshError;
+    type RefreshError = errors::RefreshError;
     type ResolveError = errors::ResolveError;
     type DownloadError = errors::DownloadError;
 
-    fn refresh(&self, project: &Project) -> Result<(), Self::RefreshError> {
-        GitBasedSource::refresh(self, project)
+    fn refresh(
+        &self,
+        project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
+        // fallback registries are refreshed along with the index, as resolving may search them
+        let mut queue = VecDeque::from([self.clone()]);
+        let mut visited = HashSet::new();
+
+        while let Some(source) = queue.pop_front() {
+            if !visited.insert(source.repo_url.clone()) {
+                continue;
+            }
+
+            GitBasedSource::refresh(&source, project).map_err(Box::new)?;
+            queue.extend(source.fallbacks(project).map_err(Box::new)?);
+        }
+
+        Ok(())
     }
 
     fn resolve(
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        _reqwest: &reqwest::blocking::Client,
         _package_target: TargetKind,
     ) -> Result<crate::source::ResolveResult<Self::Ref>, Self::ResolveError> {
         let (scope, name) = specifier.name.as_str();
-        let string = match self.read_file([scope, name], project, None) {
-            Ok(Some(s)) => s,
-            Ok(None) => return Err(Self::ResolveError::NotFound(specifier.name.to_string())),
-            Err(e) => {
-                return Err(Self::ResolveError::Read(
-                    specifier.name.to_string(),
-                    Box::new(e),
-                ))
+
+        // search the index, then its fallback registries in the order they are declared
+        let mut queue = VecDeque::from([self.clone()]);
+        let mut visited = HashSet::new();
+
+        let (source, string) = loop {
+            let Some(source) = queue.pop_front() else {
+                return Err(Self::ResolveError::NotFound(specifier.name.to_string()));
+            };
+
+            if !visited.insert(source.repo_url.clone()) {
+                continue;
+            }
+
+            match source.read_file([scope, name], project, None) {
+                Ok(Some(s)) => break (source, s),
+                Ok(None) => {}
+                Err(e) => {
+                    return Err(Self::ResolveError::Read(
+                        specifier.name.to_string(),
+                        Box::new(e),
+                    ))
+                }
             }
+
+            queue.extend(source.fallbacks(project).map_err(Box::new)?);
         };
 
+        if source != *self {
+            log::debug!(
+                "found {} in fallback registry {}",
+                specifier.name,
+                source.repo_url
+            );
+        }
+
         let entries: Vec<WallyManifest> = string
             .lines()
             .map(serde_json::from_str)
@@ -126,11 +181,12 @@ impl PackageSource for WallyPackageSource {
                         VersionId(manifest.package.version.clone(), TargetKind::Roblox),
                         WallyPackageRef {
                             name: specifier.name.clone(),
-                            index_url: self.repo_url.clone(),
+                            index_url: source.repo_url.clone(),
                             dependencies: manifest.all_dependencies().map_err(|e| {
                                 Self::ResolveError::AllDependencies(specifier.to_string(), e)
                             })?,
                             version: manifest.package.version,
+                            archive_hash: None,
                         },
                     ))
                 })
@@ -140,36 +196,62 @@ impl PackageSource for WallyPackageSource {
 
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         reqwest: &reqwest::blocking::Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError> {
         let config = self.config(project).map_err(Box::new)?;
-        let index_file = project
-            .cas_dir
-            .join("wally_index")
-            .join(pkg_ref.name.escaped())
-            .join(pkg_ref.version.to_string());
-
-        let tempdir = match std::fs::read_to_string(&index_file) {
-            Ok(s) => {
-                log::debug!(
-                    "using cached index file for package {}@{}",
-                    pkg_ref.name,
-                    pkg_ref.version
-                );
-
-                let tempdir = tempdir()?;
-                let fs = toml::from_str::<PackageFS>(&s)?;
-
-                fs.write_to(&tempdir, project.cas_dir(), false)?;
-
-                return Ok((fs, get_target(project, &tempdir)?));
-            }
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => tempdir()?,
+        let index_file = pkg_ref.index_file(project);
+        let hash_file = pkg_ref.hash_file(project);
+        let _lock = project.lock_path(&index_file)?;
+
+        let cached = match std::fs::read_to_string(&index_file) {
+            Ok(s) => match std::fs::read_to_string(&hash_file) {
+                Ok(hash) => Some((s, hash.trim().to_string())),
+                // without the archive's hash the cached file system can't be verified, so download it again
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    log::debug!(
+                        "no archive hash cached for package {}@{}, downloading it again",
+                        pkg_ref.name,
+                        pkg_ref.version
+                    );
+                    None
+                }
+                Err(e) => return Err(errors::DownloadError::ReadIndex(e)),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
             Err(e) => return Err(errors::DownloadError::ReadIndex(e)),
         };
 
+        if let Some((s, cached_hash)) = cached {
+            match &pkg_ref.archive_hash {
+                Some(expected) if *expected != cached_hash => {
+                    return Err(errors::DownloadError::HashMismatch(
+                        format!("{}@{}", pkg_ref.name, pkg_ref.version),
+                        expected.clone(),
+                        cached_hash,
+                    ));
+                }
+                Some(_) => {}
+                None => pkg_ref.archive_hash = Some(cached_hash),
+            }
+
+            log::debug!(
+                "using cached index file for package {}@{}",
+                pkg_ref.name,
+                pkg_ref.version
+            );
+
+            let tempdir = tempdir()?;
+            let fs = toml::from_str::<PackageFS>(&s)?;
+
+            fs.write_to(&tempdir, project.cas_dir(), LinkStrategy::Copy)?;
+
+            return Ok((fs, get_target(project, &tempdir)?));
+        }
+
+        let tempdir = tempdir()?;
+
         let (scope, name) = pkg_ref.name.as_str();
 
         let url = format!(
@@ -177,21 +259,47 @@ impl PackageSource for WallyPackageSource {
             config.api.as_str().trim_end_matches('/'),
             pkg_ref.version
         );
+        let url = project.mirror_config.rewrite(&url).unwrap_or(url);
 
-        let mut request = reqwest.get(&url).header(
-            "Wally-Version",
-            std::env::var("PESDE_WALLY_VERSION")
-                .as_deref()
-                .unwrap_or("0.3.2"),
-        );
-
-        if let Some(token) = project.auth_config.tokens().get(&self.repo_url) {
+        let wally_version =
+            std::env::var("PESDE_WALLY_VERSION").unwrap_or_else(|_| "0.3.2".to_string());
+        let token = project.auth_config.tokens().get(&self.repo_url);
+        if token.is_some() {
             log::debug!("using token for {}", self.repo_url);
-            request = request.header(AUTHORIZATION, token);
         }
 
-        let response = request.send()?.error_for_status()?;
-        let bytes = response.bytes()?;
+        let bytes = project.retry_config.download(
+            &url,
+            || {
+                let request = reqwest.get(&url).header("Wally-Version", &wally_version);
+
+                match token {
+                    Some(token) => request.header(AUTHORIZATION, token),
+                    None => request,
+                }
+            },
+            |downloaded, total| {
+                project.report(ProgressEvent::DownloadProgress {
+                    package: None,
+                    url: url.clone(),
+                    downloaded,
+                    total,
+                })
+            },
+        )?;
+
+        let archive_hash = hash(&bytes);
+        match &pkg_ref.archive_hash {
+            Some(expected) if *expected != archive_hash => {
+                return Err(errors::DownloadError::HashMismatch(
+                    format!("{}@{}", pkg_ref.name, pkg_ref.version),
+                    expected.clone(),
+                    archive_hash,
+                ));
+            }
+            Some(_) => {}
+            None => pkg_ref.archive_hash = Some(archive_hash.clone()),
+        }
 
         let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes))?;
         archive.extract(tempdir.path())?;
@@ -220,8 +328,15 @@ impl PackageSource for WallyPackageSource {
             }
 
             let mut file = std::fs::File::open(entry.path())?;
+            #[cfg(unix)]
+            let mode = {
+                use std::os::unix::fs::PermissionsExt;
+                crate::source::fs::file_mode(file.metadata()?.permissions().mode())
+            };
+            #[cfg(not(unix))]
+            let mode = None;
             let hash = store_reader_in_cas(project.cas_dir(), &mut file)?;
-            entries.insert(path, FSEntry::File(hash));
+            entries.insert(path, FSEntry::File(hash, mode));
         }
 
         let fs = PackageFS::CAS(entries);
@@ -232,6 +347,7 @@ impl PackageSource for WallyPackageSource {
 
         std::fs::write(&index_file, toml::to_string(&fs)?)
             .map_err(errors::DownloadError::WriteIndex)?;
+        std::fs::write(&hash_file, archive_hash).map_err(errors::DownloadError::WriteIndex)?;
 
         Ok((fs, get_target(project, &tempdir)?))
     }
@@ -241,6 +357,8 @@ impl PackageSource for WallyPackageSource {
 #[derive(Debug, Clone, Deserialize)]
 pub struct WallyIndexConfig {
     api: url::Url,
+    #[serde(default, deserialize_with = "crate::util::deserialize_gix_url_vec")]
+    fallback_registries: Vec<gix::Url>,
 }
 
 /// Errors that can occur when interacting with a Wally package source
@@ -249,6 +367,19 @@ pub mod errors {
 
     use crate::source::git_index::errors::ReadFile;
 
+    /// Errors that can occur when refreshing a Wally package source
+    #[derive(Debug, Error)]
+    #[non_exhaustive]
+    pub enum RefreshError {
+        /// Error refreshing the index or one of its fallback registries
+        #[error("error refreshing index")]
+        Refresh(#[from] Box<crate::source::git_index::errors::RefreshError>),
+
+        /// Error reading the config of the index or one of its fallback registries
+        #[error("error reading config of index")]
+        Config(#[from] Box<ConfigError>),
+    }
+
     /// Errors that can occur when resolving a package from a Wally package source
     #[derive(Debug, Error)]
     #[non_exhaustive]
@@ -279,6 +410,10 @@ pub mod errors {
             String,
             #[source] crate::manifest::errors::AllDependenciesError,
         ),
+
+        /// Error reading the config of the index or one of its fallback registries
+        #[error("error reading config of index")]
+        Config(#[from] Box<ConfigError>),
     }
 
     /// Errors that can occur when reading the config file for a Wally package source
@@ -308,7 +443,7 @@ pub mod errors {
 
         /// Error downloading package
         #[error("error downloading package")]
-        Download(#[from] reqwest::Error),
+        Download(#[from] crate::retry::errors::RetryError),
 
         /// Error deserializing index file
         #[error("error deserializing index file")]
@@ -341,5 +476,9 @@ pub mod errors {
         /// Error writing index file
         #[error("error writing index file")]
         WriteIndex(#[source] std::io::Error),
+
+        /// The downloaded archive's hash doesn't match the one in the lockfile
+        #[error("archive hash mismatch for {0}, expected {1} but got {2}")]
+        HashMismatch(String, String, String),
     }
 }
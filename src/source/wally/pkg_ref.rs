@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -7,6 +7,7 @@ use crate::{
     manifest::{target::TargetKind, DependencyType},
     names::wally::WallyPackageName,
     source::{wally::WallyPackageSource, DependencySpecifiers, PackageRef, PackageSources},
+    Project,
 };
 
 /// A Wally package reference
@@ -26,7 +27,27 @@ pub struct WallyPackageRef {
     /// The dependencies of the package
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub dependencies: BTreeMap<String, (DependencySpecifiers, DependencyType)>,
+    /// The SHA-256 hash of the package's archive, recorded when it's first downloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_hash: Option<String>,
 }
+impl WallyPackageRef {
+    /// The path of the cached file system of this package
+    pub(crate) fn index_file(&self, project: &Project) -> PathBuf {
+        project
+            .cas_dir
+            .join("wally_index")
+            .join(self.name.escaped())
+            .join(self.version.to_string())
+    }
+
+    /// The path of the file holding the hash of this package's archive, beside its cached file system
+    pub(crate) fn hash_file(&self, project: &Project) -> PathBuf {
+        self.index_file(project)
+            .with_file_name(format!("{}.sha256", self.version))
+    }
+}
+
 impl PackageRef for WallyPackageRef {
     fn dependencies(&self) -> &BTreeMap<String, (DependencySpecifiers, DependencyType)> {
         &self.dependencies
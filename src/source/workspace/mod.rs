@@ -27,7 +27,11 @@ impl PackageSource for WorkspacePackageSource {
     type ResolveError = errors::ResolveError;
     type DownloadError = errors::DownloadError;
 
-    fn refresh(&self, _project: &Project) -> Result<(), Self::RefreshError> {
+    fn refresh(
+        &self,
+        _project: &Project,
+        _reqwest: &reqwest::blocking::Client,
+    ) -> Result<(), Self::RefreshError> {
         // no-op
         Ok(())
     }
@@ -36,6 +40,7 @@ impl PackageSource for WorkspacePackageSource {
         &self,
         specifier: &Self::Specifier,
         project: &Project,
+        _reqwest: &reqwest::blocking::Client,
         package_target: TargetKind,
     ) -> Result<ResolveResult<Self::Ref>, Self::ResolveError> {
         let (path, manifest) = 'finder: {
@@ -107,7 +112,21 @@ impl PackageSource for WorkspacePackageSource {
                                     )
                                 }
                                 DependencySpecifiers::Git(_) => {}
+                                DependencySpecifiers::Url(_) => {}
+                                DependencySpecifiers::Custom(_) => {}
                                 DependencySpecifiers::Workspace(_) => {}
+                                // the dependency's path is relative to the member, but it will be
+                                // resolved relative to the project, so it has to be rebased
+                                DependencySpecifiers::Path(spec) => {
+                                    let member_dir =
+                                        pathdiff::diff_paths(&path, project.package_dir()).unwrap();
+
+                                    spec.path = RelativePathBuf::from_path(member_dir)
+                                        .map_err(|_| {
+                                            errors::ResolveError::InvalidMemberPath(path.clone())
+                                        })?
+                                        .join_normalized(&spec.path);
+                                }
                             }
 
                             Ok((alias, (spec, ty)))
@@ -121,7 +140,7 @@ impl PackageSource for WorkspacePackageSource {
 
     fn download(
         &self,
-        pkg_ref: &Self::Ref,
+        pkg_ref: &mut Self::Ref,
         project: &Project,
         _reqwest: &Client,
     ) -> Result<(PackageFS, Target), Self::DownloadError> {
@@ -137,6 +156,7 @@ impl PackageSource for WorkspacePackageSource {
 /// Errors that can occur when using a workspace package source
 pub mod errors {
     use crate::manifest::target::TargetKind;
+    use std::path::PathBuf;
     use thiserror::Error;
 
     /// Errors that can occur when refreshing the workspace package source
@@ -163,6 +183,10 @@ pub mod errors {
         /// An index of a member package was not found
         #[error("index {0} not found in member {1}")]
         IndexNotFound(String, String),
+
+        /// The path of a member package can't be represented as a relative path
+        #[error("path of member {0} is not a valid relative path")]
+        InvalidMemberPath(PathBuf),
     }
 
     /// Errors that can occur when downloading a workspace package
@@ -13,6 +13,8 @@ pub fn authenticate_conn(
     auth_config: &AuthConfig,
 ) {
     if let Some(iden) = auth_config.git_credentials().cloned() {
+        // the error type is dictated by gix
+        #[allow(clippy::result_large_err)]
         conn.set_credentials(move |action| match action {
             gix::credentials::helper::Action::Get(ctx) => {
                 Ok(Some(gix::credentials::protocol::Outcome {
@@ -61,6 +63,42 @@ pub fn deserialize_gix_url_map<'de, D: Deserializer<'de>>(
         .collect()
 }
 
+pub fn serialize_gix_url_pairs<S: Serializer>(
+    urls: &BTreeMap<gix::Url, gix::Url>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut map = serializer.serialize_map(Some(urls.len()))?;
+    for (k, v) in urls {
+        map.serialize_entry(&k.to_bstring().to_string(), &v.to_bstring().to_string())?;
+    }
+    map.end()
+}
+
+pub fn deserialize_gix_url_pairs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<gix::Url, gix::Url>, D::Error> {
+    BTreeMap::<String, String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(k, v)| {
+            Ok((
+                gix::Url::from_bytes(BStr::new(&k))?,
+                gix::Url::from_bytes(BStr::new(&v))?,
+            ))
+        })
+        .collect::<Result<_, gix::url::parse::Error>>()
+        .map_err(serde::de::Error::custom)
+}
+
+#[cfg(feature = "wally-compat")]
+pub fn deserialize_gix_url_vec<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<gix::Url>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|v| gix::Url::from_bytes(BStr::new(&v)).map_err(serde::de::Error::custom))
+        .collect()
+}
+
 pub fn deserialize_git_like_url<'de, D: Deserializer<'de>>(
     deserializer: D,
 ) -> Result<gix::Url, D::Error> {
@@ -73,6 +111,12 @@ pub fn deserialize_git_like_url<'de, D: Deserializer<'de>>(
     }
 }
 
+pub fn deserialize_lowercase_opt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.map(|s| s.to_lowercase()))
+}
+
 pub fn hash<S: AsRef<[u8]>>(struc: S) -> String {
     format!("{:x}", Sha256::digest(struc.as_ref()))
 }